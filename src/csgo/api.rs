@@ -1,151 +1,581 @@
+use std::collections::{BTreeMap, HashSet};
+
 use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveTime};
-use reqwest::header::{self, HeaderMap, HeaderValue};
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::csgo::client::{ClientOptions, HttpClient};
+use crate::csgo::detail::{Detail, MapBpDto, MatchDetailDto, PlayerStatsDto, SingleMatchDataDto};
+use crate::csgo::error::ApiError;
+use crate::csgo::game::Game;
+use crate::csgo::provider::MatchProvider;
+use crate::csgo::stream::{Stream, StreamUrls};
+use crate::csgo::tournament::Tournament;
+use crate::notify::Card;
+use crate::utils::{Event, Store};
+
+const MAP_DURATION: i64 = 60 * 60;
+const DEFAULT_MAPS: i64 = 2;
+const DEFAULT_CONCURRENCY: usize = 3;
+const DEFAULT_API_URL: &str = "https://gwapi.pwesports.cn";
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGES: usize = 20;
+const SUCCESS_CODES: [i32; 2] = [0, 200];
 
 #[derive(Debug, Clone)]
 pub struct CsgoApi {
-    client: Client,
+    client: HttpClient,
+    game: Game,
+    base_url: String,
+    page_size: usize,
     time: NaiveTime,
     time_format: String,
-    teams: Vec<i32>,
+    concurrency: usize,
+    streams: StreamUrls,
 }
 
 impl CsgoApi {
-    pub fn new() -> Result<CsgoApi> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            header::ACCEPT_ENCODING,
-            HeaderValue::from_static("gzip, deflate, br"),
-        );
-        headers.insert(
-            header::USER_AGENT,
-            HeaderValue::from_static("PostmanRuntime/7.32.3"),
-        );
-        headers.insert(header::HOST, HeaderValue::from_static("localhost"));
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .context("build request client failed")?;
+    pub fn new(concurrency: usize, options: ClientOptions) -> Result<CsgoApi> {
+        let client = HttpClient::new(options).context("init http client failed")?;
         let time = NaiveTime::from_hms_opt(0, 0, 0)
             .ok_or(anyhow!("error default time"))
             .context("set zero time failed")?;
         let time_format = "%Y-%m-%d+%H:%M:%S".to_string();
-        let teams = vec![6667, 5995, 12396, 4608, 5378, 8840, 5752];
 
         Ok(Self {
             client,
+            game: Game::default(),
+            base_url: DEFAULT_API_URL.to_string(),
+            page_size: DEFAULT_PAGE_SIZE,
             time,
             time_format,
-            teams,
+            concurrency: concurrency.max(1),
+            streams: StreamUrls::default(),
+        })
+    }
+
+    pub fn from_env(game: Game) -> Result<CsgoApi> {
+        let env = game.env();
+        let concurrency = env.parse("CONCURRENCY", DEFAULT_CONCURRENCY)?;
+        let options = ClientOptions::from_env(&env).context("init client options failed")?;
+        let page_size = env.parse("PAGE_SIZE", DEFAULT_PAGE_SIZE)?;
+        let streams = StreamUrls::from_env().context("init stream urls failed")?;
+        let api = Self::new(concurrency, options)?
+            .with_game(game)
+            .with_page_size(page_size)
+            .with_streams(streams);
+
+        Ok(match env.var("API_URL") {
+            Some(base_url) => api.with_base_url(base_url),
+            None => api,
         })
     }
 
-    pub async fn get_matches_by_date(&self, date: &NaiveDate) -> Result<Vec<Match>> {
+    pub fn with_game(mut self, game: Game) -> Self {
+        self.game = game;
+
+        self
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+
+        self
+    }
+
+    pub fn with_streams(mut self, streams: StreamUrls) -> Self {
+        self.streams = streams;
+
+        self
+    }
+
+    /// Points the api at another server, e.g. a mirror or a local stand-in.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+
+        self
+    }
+
+    async fn get_page(&self, date: &NaiveDate, page: usize) -> Result<MatchResponse, ApiError> {
         let url = format!(
-            "https://gwapi.pwesports.cn/eventcenter/app/csgo/event/getMatchList?matchTime={}",
-            date.and_time(self.time).format(&self.time_format)
+            "{}/eventcenter/app/{}/event/getMatchList?matchTime={}&pageNum={}&pageSize={}",
+            self.base_url,
+            self.game,
+            date.and_time(self.time).format(&self.time_format),
+            page,
+            self.page_size
         );
 
+        let body = self.client.get(&url).await?;
+        let response = serde_json::from_slice::<Response>(&body).map_err(ApiError::Decode)?;
+        if !SUCCESS_CODES.contains(&response.code) {
+            return Err(ApiError::Envelope {
+                code: response.code,
+                message: response.message.unwrap_or_default(),
+            });
+        }
+        let response = match response.result {
+            Some(result) => {
+                serde_json::from_value::<MatchResult>(result)
+                    .map_err(ApiError::Decode)?
+                    .match_response
+            }
+            None => MatchResponse::default(),
+        };
+
+        Ok(response)
+    }
+
+    /// Follows the pages of a date until `item_count` items are collected or a page brings
+    /// nothing new, which also covers servers ignoring the page parameters.
+    async fn get_list_by_date(&self, date: &NaiveDate) -> Result<Vec<DtoList>, ApiError> {
+        let mut items = Vec::new();
+        let mut ids = HashSet::new();
+        let mut received = 0;
+        for page in 1..=MAX_PAGES {
+            let response = self.get_page(date, page).await?;
+            let count = response.dto_list.len();
+            received += count;
+            let before = items.len();
+            for item in response.dto_list {
+                let id = item.get("matchId").map(ToString::to_string);
+                if id.is_none_or(|id| ids.insert(id)) {
+                    items.push(item);
+                }
+            }
+
+            let total = response.item_count.unwrap_or_default().max(0) as usize;
+            if received >= total || count == 0 {
+                break;
+            }
+            // repeated pages or too many of them, keep what was received
+            if items.len() == before || page == MAX_PAGES {
+                warn!(
+                    "only {} of {} matches of `{}` received",
+                    items.len(),
+                    total,
+                    date
+                );
+                break;
+            }
+        }
+
+        // a malformed item should not cost the whole day
+        let list = items
+            .into_iter()
+            .filter_map(|item| {
+                let id = item.get("matchId").cloned().unwrap_or_default();
+                match serde_json::from_value::<DtoList>(item) {
+                    Ok(item) => Some(item),
+                    Err(e) => {
+                        warn!("skip malformed match `{}` of `{}`: {}", id, date, e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<DtoList>>();
+
+        Ok(list)
+    }
+
+    pub async fn get_matches_by_date(&self, date: &NaiveDate) -> Result<Vec<Match>, ApiError> {
         let matches = self
-            .client
-            .get(url)
-            .header(header::HOST, "gwapi.pwesports.cn")
-            .send()
-            .await
-            .context("send request failed")?
-            .json::<Response>()
-            .await
-            .context("get response failed")?
-            .result
-            .match_response
-            .dto_list
+            .get_list_by_date(date)
+            .await?
             .into_iter()
-            .filter(|item| self.teams.contains(&item.team1id) || self.teams.contains(&item.team2id))
             .map(|item| {
-                Match::new(
-                    (item.team1dto.name, item.team1dto.logo_white),
-                    (item.team2dto.name, item.team2dto.logo_white),
-                    (item.start_time / 1000, item.bo, item.csgo_event_dto.name),
-                )
+                let mut item = Match::from(item);
+                item.game = self.game;
+                item.stream = item.stream.map(|stream| self.streams.resolve(stream));
+                item
             })
             .collect::<Vec<Match>>();
 
         Ok(matches)
     }
+
+    /// Requests at most `concurrency` dates at the same time, matches keep the order of dates.
+    pub async fn get_matches_by_dates(&self, dates: &[NaiveDate]) -> Result<Vec<Match>> {
+        let matches = stream::iter(dates.iter().copied())
+            .map(|date| async move {
+                self.get_matches_by_date(&date)
+                    .await
+                    .with_context(|| format!("get matches of `{}` failed", date))
+            })
+            .buffered(self.concurrency)
+            .try_collect::<Vec<Vec<Match>>>()
+            .await?;
+
+        Ok(matches.into_iter().flatten().collect())
+    }
+
+    /// Collects every team playing on the given dates, dates failing to load are skipped.
+    pub async fn discover_teams(&self, dates: &[NaiveDate]) -> TeamIndex {
+        let mut index = TeamIndex::default();
+        for date in dates {
+            match self.get_list_by_date(date).await {
+                Ok(list) => {
+                    for item in list {
+                        index.insert(item.team1dto);
+                        index.insert(item.team2dto);
+                    }
+                }
+                Err(e) => warn!(
+                    "get teams of `{}` failed: {:?}",
+                    date,
+                    anyhow::Error::from(e)
+                ),
+            }
+        }
+
+        index
+    }
 }
 
-#[derive(Debug, Serialize)]
-#[allow(dead_code)]
+#[async_trait]
+impl MatchProvider for CsgoApi {
+    fn name(&self) -> &str {
+        "pwesports"
+    }
+
+    async fn get_matches_by_dates(&self, dates: &[NaiveDate]) -> Result<Vec<Match>> {
+        CsgoApi::get_matches_by_dates(self, dates).await
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamInfo {
+    pub id: i32,
+    pub name: String,
+    pub logo: String,
+    pub rank: Option<i32>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TeamIndex {
+    teams: BTreeMap<i32, TeamInfo>,
+}
+
+impl TeamIndex {
+    fn insert(&mut self, team: TeamDto) {
+        let info = TeamInfo {
+            id: team.team_id,
+            name: team.name,
+            logo: team.logo_white,
+            rank: team.rank,
+            location: team.location,
+        };
+        self.teams.insert(info.id, info);
+    }
+
+    /// Adds the teams saved as `<game>.teams` in `store` and saves the result, so
+    /// teams are still found when recent matches could not be fetched.
+    pub fn persist(self, game: Game, store: &Store) -> Result<TeamIndex> {
+        let name = format!("{}.teams", game);
+        let mut index = store
+            .load::<TeamIndex>(&name)
+            .context("load team index failed")?
+            .unwrap_or_default();
+        // fresh infos win, ranks change
+        index.teams.extend(self.teams);
+        store
+            .save(&name, &index)
+            .context("save team index failed")?;
+
+        Ok(index)
+    }
+
+    /// Teams whose id equals `query` or whose name contains it, ignoring case.
+    pub fn search(&self, query: &str) -> Vec<&TeamInfo> {
+        let query = query.trim().to_lowercase();
+
+        self.teams
+            .values()
+            .filter(|team| {
+                query.is_empty()
+                    || team.id.to_string() == query
+                    || team.name.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Match {
-    team1: Team,
-    team2: Team,
-    info: Info,
+    pub id: String,
+    #[serde(default)]
+    pub game: Game,
+    pub team1: Team,
+    pub team2: Team,
+    pub info: Info,
+    #[serde(default)]
+    pub state: State,
+    #[serde(default)]
+    pub detail: Option<Detail>,
+    #[serde(default)]
+    pub stream: Option<Stream>,
+    #[serde(default)]
+    pub tournament: Option<Tournament>,
 }
 
-impl Match {
-    fn new(team1: impl Into<Team>, team2: impl Into<Team>, info: impl Into<Info>) -> Match {
+impl From<DtoList> for Match {
+    fn from(item: DtoList) -> Self {
+        let event = item.csgo_event_dto;
+        let tournament = Tournament {
+            id: event.event_id,
+            name: event.name.clone(),
+            name_zh: event.name_zh.filter(|name| !name.is_empty()),
+            logo: event.logo,
+            start_time: event.start_time / 1000,
+            end_time: event.end_time / 1000,
+            prize: event.prize.clone(),
+            important: event.important,
+        };
+        let info = Info {
+            start_time: item.start_time / 1000,
+            bo: item.bo,
+            name: event.name,
+            star: item.star,
+            hot: event.hot,
+            important: event.important,
+            prize: event.prize,
+            stage: item
+                .description
+                .or(item.stage_id)
+                .filter(|stage| !stage.is_empty()),
+        };
+
         Self {
-            team1: team1.into(),
-            team2: team2.into(),
-            info: info.into(),
+            id: item.match_id.to_string(),
+            game: Game::default(),
+            team1: Team {
+                id: item.team1id,
+                ..Team::from(item.team1dto)
+            },
+            team2: Team {
+                id: item.team2id,
+                ..Team::from(item.team2dto)
+            },
+            info,
+            state: State::new(item.status, item.score1, item.score2, item.winner_team_id),
+            detail: Detail::new(
+                item.map_bpdtos,
+                item.single_match_data_dtos,
+                item.performance_stats_list.or(item.stats_dtolist),
+                item.match_detail_dto,
+            ),
+            stream: item
+                .platform
+                .zip(item.room_id)
+                .and_then(|(platform, room)| Stream::new(platform, room)),
+            tournament: Some(tournament),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
-#[allow(dead_code)]
-pub struct Info {
-    start_time: i64,
-    bo: String,
-    name: String,
+/// Rooms come as numbers or strings depending on the platform.
+fn deserialize_text<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(
+        Option::<serde_json::Value>::deserialize(deserializer)?.and_then(|value| match value {
+            serde_json::Value::String(text) => Some(text),
+            serde_json::Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        }),
+    )
 }
 
-impl Info {
-    fn new(start_time: i64, bo: impl Into<String>, name: impl Into<String>) -> Info {
-        Self {
-            start_time,
-            bo: bo.into(),
-            name: name.into(),
+impl Match {
+    pub fn winner(&self) -> Option<&Team> {
+        match self.winner_side()? {
+            1 => Some(&self.team1),
+            2 => Some(&self.team2),
+            _ => None,
         }
     }
+
+    /// `1` or `2` for the side of the winner.
+    pub(crate) fn winner_side(&self) -> Option<u8> {
+        match (self.state.winner_side, self.state.winner) {
+            (Some(side), _) => Some(side),
+            (None, Some(winner)) if winner == self.team1.id => Some(1),
+            (None, Some(winner)) if winner == self.team2.id => Some(2),
+            _ => None,
+        }
+    }
+
+    pub fn card(&self) -> Card {
+        let card = Card::new(format!("{} vs {}", self.team1.name, self.team2.name))
+            .with_description(&self.info.name)
+            .with_field("BO", self.info.bo.to_uppercase())
+            .with_icon(&self.team1.logo, &self.team1.name)
+            .with_icon(&self.team2.logo, &self.team2.name)
+            .with_timestamp(self.info.start_time);
+
+        match self.watch_url() {
+            Some(url) => card.with_url(url),
+            None => card,
+        }
+    }
+
+    /// Where the match is streamed, when the platform is known.
+    pub fn watch_url(&self) -> Option<&str> {
+        self.stream.as_ref()?.url.as_deref()
+    }
+
+    pub fn event(&self) -> Event {
+        Event {
+            uid: format!("{}-{}@iknow", self.game, self.id),
+            start: self.info.start_time,
+            end: self.info.start_time + self.info.duration(),
+            summary: format!("{} vs {}", self.team1.name, self.team2.name),
+            description: Some(format!(
+                "{} {}",
+                self.info.name,
+                self.info.bo.to_uppercase()
+            )),
+            url: self.watch_url().map(ToString::to_string),
+            cancelled: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Info {
+    pub start_time: i64,
+    pub bo: String,
+    pub name: String,
+    #[serde(default)]
+    pub star: i32,
+    #[serde(default)]
+    pub hot: bool,
+    #[serde(default)]
+    pub important: bool,
+    #[serde(default)]
+    pub prize: Option<String>,
+    /// Stage of the event like `Playoffs`, when the source tells.
+    #[serde(default)]
+    pub stage: Option<String>,
 }
 
-impl From<(i64, String, String)> for Info {
-    fn from(value: (i64, String, String)) -> Self {
-        Self::new(value.0, value.1, value.2)
+impl Info {
+    /// Estimated duration in seconds, one hour for every map of the series.
+    pub fn duration(&self) -> i64 {
+        self.maps().unwrap_or(DEFAULT_MAPS).max(1) * MAP_DURATION
+    }
+
+    /// Number of maps of the series, `bo3` has 3 maps.
+    pub fn maps(&self) -> Option<i64> {
+        self.bo
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .parse::<i64>()
+            .ok()
     }
 }
 
-#[derive(Debug, Serialize)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Team {
-    name: String,
-    logo: String,
+    #[serde(default)]
+    pub id: i32,
+    pub name: String,
+    pub logo: String,
+    /// World ranking, `1` is the best.
+    #[serde(default)]
+    pub rank: Option<i32>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub players: Vec<Player>,
 }
 
-impl Team {
-    fn new(name: impl Into<String>, logo: impl Into<String>) -> Team {
+impl From<TeamDto> for Team {
+    fn from(team: TeamDto) -> Self {
         Self {
-            name: name.into(),
-            logo: logo.into(),
+            id: team.team_id,
+            name: team.name,
+            logo: team.logo_white,
+            rank: team.rank.filter(|rank| *rank > 0),
+            location: team.location,
+            players: team
+                .player_dtolist
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(PlayerDto::into_player)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+    pub name: String,
+    #[serde(default)]
+    pub real_name: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    Upcoming,
+    Live,
+    Finished,
+}
+
+impl From<i32> for Status {
+    /// The api reports `0` before, `1` during and `2` after a match.
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Status::Live,
+            2 => Status::Finished,
+            _ => Status::Upcoming,
         }
     }
 }
 
-impl From<(String, String)> for Team {
-    fn from(value: (String, String)) -> Self {
-        Self::new(value.0, value.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct State {
+    pub status: Status,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub winner: Option<i32>,
+    /// `1` or `2`, for sources whose teams have no ids.
+    #[serde(default)]
+    pub winner_side: Option<u8>,
+}
+
+impl State {
+    fn new(status: i32, score1: Option<i32>, score2: Option<i32>, winner: Option<i32>) -> State {
+        // a match with a winner is over whatever the status says
+        let status = match winner {
+            Some(_) => Status::Finished,
+            None => Status::from(status),
+        };
+
+        Self {
+            status,
+            score1,
+            score2,
+            winner,
+            winner_side: None,
+        }
     }
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 struct Response {
     pub code: i32,
-    pub message: String,
-    pub result: MatchResult,
+    pub message: Option<String>,
+    pub result: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -155,14 +585,12 @@ struct MatchResult {
     pub match_response: MatchResponse,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[allow(dead_code)]
 struct MatchResponse {
-    pub dto_list: Vec<DtoList>,
-    pub item_count: i32,
-    #[serde(rename = "csgoEventDTO")]
-    pub csgo_event_dto: Option<String>,
+    #[serde(default)]
+    pub dto_list: Vec<serde_json::Value>,
+    pub item_count: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -190,24 +618,32 @@ struct DtoList {
     pub match_type: i32,
     pub status: i32,
     #[serde(rename = "statsDTOList")]
-    pub stats_dtolist: Option<String>,
-    #[serde(rename = "csgoEventDTO")]
+    pub stats_dtolist: Option<Vec<PlayerStatsDto>>,
+    #[serde(
+        rename = "csgoEventDTO",
+        alias = "dota2EventDTO",
+        alias = "lolEventDTO",
+        alias = "valorantEventDTO",
+        alias = "eventDTO"
+    )]
     pub csgo_event_dto: CsgoEventDto,
     #[serde(rename = "matchDetailDTO")]
-    pub match_detail_dto: Option<String>,
+    pub match_detail_dto: Option<MatchDetailDto>,
     #[serde(rename = "mapBPDTOS")]
-    pub map_bpdtos: Option<String>,
+    pub map_bpdtos: Option<Vec<MapBpDto>>,
     #[serde(rename = "singleMatchDataDTOS")]
-    pub single_match_data_dtos: Option<String>,
+    pub single_match_data_dtos: Option<Vec<SingleMatchDataDto>>,
     pub subscribe_status: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_text")]
     pub room_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_text")]
     pub platform: Option<String>,
     pub stage_id: Option<String>,
     pub news_id: i32,
     pub match_id_list: Option<String>,
     pub completed_status: Option<String>,
     pub has_predict: bool,
-    pub performance_stats_list: Option<String>,
+    pub performance_stats_list: Option<Vec<PlayerStatsDto>>,
     pub description: Option<String>,
 }
 
@@ -223,7 +659,32 @@ struct TeamDto {
     pub rank: Option<i32>,
     pub location: Option<String>,
     #[serde(rename = "playerDTOList")]
-    pub player_dtolist: Option<String>,
+    pub player_dtolist: Option<Vec<PlayerDto>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct PlayerDto {
+    pub player_id: Option<i32>,
+    #[serde(alias = "nickname", alias = "nickName")]
+    pub name: Option<String>,
+    pub real_name: Option<String>,
+    #[serde(alias = "nationality")]
+    pub country: Option<String>,
+    #[serde(alias = "logo")]
+    pub avatar: Option<String>,
+}
+
+impl PlayerDto {
+    fn into_player(self) -> Option<Player> {
+        Some(Player {
+            name: self.name.filter(|name| !name.is_empty())?,
+            real_name: self.real_name,
+            country: self.country,
+            avatar: self.avatar,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,527 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Row};
+use serde::Serialize;
+
+use crate::csgo::api::{Match, Status};
+use crate::csgo::game::Game;
+use crate::csgo::provider::{is_same_team, Fetched, MatchProvider, SAME_MATCH_TOLERANCE};
+use crate::utils::{Env, Store, Task, Window};
+
+const DEFAULT_NAME: &str = "archive.sqlite";
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS matches (
+    game TEXT NOT NULL,
+    id TEXT NOT NULL,
+    start_time INTEGER NOT NULL,
+    bo TEXT NOT NULL,
+    event_id INTEGER,
+    event TEXT NOT NULL,
+    stage TEXT,
+    team1_id INTEGER NOT NULL,
+    team1 TEXT NOT NULL,
+    team2_id INTEGER NOT NULL,
+    team2 TEXT NOT NULL,
+    score1 INTEGER,
+    score2 INTEGER,
+    winner TEXT,
+    status TEXT NOT NULL,
+    PRIMARY KEY (game, id)
+);
+CREATE INDEX IF NOT EXISTS matches_start_time ON matches (game, start_time);
+CREATE TABLE IF NOT EXISTS events (
+    game TEXT NOT NULL,
+    id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    name_zh TEXT,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER NOT NULL,
+    prize TEXT,
+    important INTEGER NOT NULL,
+    PRIMARY KEY (game, id)
+);
+";
+
+/// A finished match as kept in the archive.
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub id: String,
+    pub start_time: i64,
+    pub event: String,
+    pub bo: String,
+    pub team1_id: i32,
+    pub team1: String,
+    pub team2_id: i32,
+    pub team2: String,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub winner: Option<String>,
+}
+
+impl Record {
+    fn from_row(row: &Row) -> rusqlite::Result<Record> {
+        Ok(Self {
+            id: row.get("id")?,
+            start_time: row.get("start_time")?,
+            event: row.get("event")?,
+            bo: row.get("bo")?,
+            team1_id: row.get("team1_id")?,
+            team1: row.get("team1")?,
+            team2_id: row.get("team2_id")?,
+            team2: row.get("team2")?,
+            score1: row.get("score1")?,
+            score2: row.get("score2")?,
+            winner: row.get("winner")?,
+        })
+    }
+
+    /// The name of `side` as this record spells it.
+    fn name(&self, side: &Side) -> &str {
+        match side.is(self.team1_id, &self.team1) {
+            true => &self.team1,
+            false => &self.team2,
+        }
+    }
+
+    /// The record seen from `side`.
+    fn outcome(&self, side: &Side) -> Outcome {
+        let (opponent, score, opponent_score) = match side.is(self.team1_id, &self.team1) {
+            true => (&self.team2, self.score1, self.score2),
+            false => (&self.team1, self.score2, self.score1),
+        };
+        let name = self.name(side);
+
+        Outcome {
+            opponent: opponent.clone(),
+            score,
+            opponent_score,
+            won: self.winner.as_ref().map(|winner| winner == name),
+            start_time: self.start_time,
+            event: self.event.clone(),
+        }
+    }
+}
+
+/// A team asked for by id, or by name together with the ids archived under that
+/// name, so renamed teams keep their results.
+struct Side {
+    name: Option<String>,
+    ids: Vec<i32>,
+}
+
+impl Side {
+    fn is(&self, id: i32, name: &str) -> bool {
+        self.ids.contains(&id) || self.name.as_deref() == Some(name.to_lowercase().as_str())
+    }
+
+    /// SQL condition on the team in `column`, with its parameters.
+    fn condition(&self, column: &str) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(name) = &self.name {
+            conditions.push(format!("lower({}) = ?", column));
+            values.push(Value::Text(name.clone()));
+        }
+        if !self.ids.is_empty() {
+            let holders = vec!["?"; self.ids.len()].join(", ");
+            conditions.push(format!("{}_id IN ({})", column, holders));
+            values.extend(self.ids.iter().map(|id| Value::Integer(*id as i64)));
+        }
+
+        (format!("({})", conditions.join(" OR ")), values)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Outcome {
+    pub opponent: String,
+    pub score: Option<i32>,
+    pub opponent_score: Option<i32>,
+    /// `None` when the archive does not know the winner.
+    pub won: Option<bool>,
+    pub start_time: i64,
+    pub event: String,
+}
+
+/// Last results of a team, the latest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct Form {
+    pub team: String,
+    pub results: Vec<Outcome>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadToHead {
+    pub team1: String,
+    pub team2: String,
+    pub wins1: usize,
+    pub wins2: usize,
+    /// Matches between both teams, the latest first.
+    pub records: Vec<Record>,
+}
+
+/// Matches and events kept in a local SQLite database, teams are looked up by id
+/// or by name ignoring case.
+#[derive(Clone)]
+pub struct Archive {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Archive {
+    pub fn open(path: impl AsRef<Path>) -> Result<Archive> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("create dir `{}` failed", dir.display()))?;
+        }
+        let connection = Connection::open(path)
+            .with_context(|| format!("open archive `{}` failed", path.display()))?;
+
+        Self::init(connection)
+    }
+
+    pub fn in_memory() -> Result<Archive> {
+        Self::init(Connection::open_in_memory().context("open archive failed")?)
+    }
+
+    /// Reads `ARCHIVE_PATH`, the archive is kept in `DATA_DIR` by default.
+    pub fn from_env() -> Result<Archive> {
+        match Env::new("ARCHIVE").var("PATH") {
+            Some(path) => Self::open(path),
+            None => Self::open(Store::from_env().dir().join(DEFAULT_NAME)),
+        }
+    }
+
+    fn init(connection: Connection) -> Result<Archive> {
+        connection
+            .execute_batch(SCHEMA)
+            .context("create archive tables failed")?;
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    fn connection(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.connection
+            .lock()
+            .map_err(|_| anyhow!("archive connection poisoned"))
+    }
+
+    /// Inserts or updates `matches` and their events, returns the number of matches.
+    ///
+    /// Another source may have archived the same match under its own id, such a
+    /// match is replaced so it is not counted twice.
+    pub fn save(&self, game: Game, matches: &[Match]) -> Result<usize> {
+        let mut connection = self.connection()?;
+        let transaction = connection
+            .transaction()
+            .context("begin archive transaction failed")?;
+        for item in matches {
+            let duplicates = duplicates(&transaction, game, item)
+                .with_context(|| format!("find duplicates of match `{}` failed", item.id))?;
+            for id in duplicates {
+                transaction
+                    .execute(
+                        "DELETE FROM matches WHERE game = ?1 AND id = ?2",
+                        params![game.slug(), id],
+                    )
+                    .with_context(|| format!("remove duplicate match `{}` failed", id))?;
+            }
+            let status = match item.state.status {
+                Status::Upcoming => "upcoming",
+                Status::Live => "live",
+                Status::Finished => "finished",
+            };
+            transaction
+                .execute(
+                    "INSERT OR REPLACE INTO matches (game, id, start_time, bo, event_id, event, \
+                     stage, team1_id, team1, team2_id, team2, score1, score2, winner, status) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                    params![
+                        game.slug(),
+                        item.id,
+                        item.info.start_time,
+                        item.info.bo,
+                        item.tournament.as_ref().map(|tournament| tournament.id),
+                        item.info.name,
+                        item.info.stage,
+                        item.team1.id,
+                        item.team1.name,
+                        item.team2.id,
+                        item.team2.name,
+                        item.state.score1,
+                        item.state.score2,
+                        item.winner().map(|team| team.name.as_str()),
+                        status,
+                    ],
+                )
+                .with_context(|| format!("archive match `{}` failed", item.id))?;
+            if let Some(tournament) = &item.tournament {
+                transaction
+                    .execute(
+                        "INSERT OR REPLACE INTO events (game, id, name, name_zh, start_time, \
+                         end_time, prize, important) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![
+                            game.slug(),
+                            tournament.id,
+                            tournament.name,
+                            tournament.name_zh,
+                            tournament.start_time,
+                            tournament.end_time,
+                            tournament.prize,
+                            tournament.important,
+                        ],
+                    )
+                    .with_context(|| format!("archive event `{}` failed", tournament.id))?;
+            }
+        }
+        transaction
+            .commit()
+            .context("commit archive transaction failed")?;
+
+        Ok(matches.len())
+    }
+
+    /// `team` is a team id or a team name, a name also matches the ids it was
+    /// archived with.
+    fn side(connection: &Connection, game: Game, team: &str) -> Result<Side> {
+        let team = team.trim();
+        if let Ok(id) = team.parse::<i32>() {
+            return Ok(Side {
+                name: None,
+                ids: vec![id],
+            });
+        }
+
+        let name = team.to_lowercase();
+        let mut statement = connection
+            .prepare(
+                "SELECT team1_id FROM matches WHERE game = ?1 AND lower(team1) = ?2 \
+                 AND team1_id <> 0 UNION SELECT team2_id FROM matches WHERE game = ?1 \
+                 AND lower(team2) = ?2 AND team2_id <> 0",
+            )
+            .context("prepare team ids query failed")?;
+        let ids = statement
+            .query_map(params![game.slug(), name], |row| row.get(0))
+            .context("query team ids failed")?
+            .collect::<rusqlite::Result<Vec<i32>>>()
+            .context("read team ids failed")?;
+
+        Ok(Side {
+            name: Some(name),
+            ids,
+        })
+    }
+
+    /// Finished matches of `team`, the latest first, `team` is a team id or a name.
+    pub fn results(&self, game: Game, team: &str, limit: usize) -> Result<Vec<Record>> {
+        let connection = self.connection()?;
+        let side = Self::side(&connection, game, team)?;
+
+        Self::query_results(&connection, game, &side, limit)
+    }
+
+    fn query_results(
+        connection: &Connection,
+        game: Game,
+        side: &Side,
+        limit: usize,
+    ) -> Result<Vec<Record>> {
+        let (first, mut values) = side.condition("team1");
+        let (second, more) = side.condition("team2");
+        values.extend(more);
+        let mut statement = connection
+            .prepare(&format!(
+                "SELECT * FROM matches WHERE game = ? AND status = 'finished' \
+                 AND ({} OR {}) ORDER BY start_time DESC LIMIT ?",
+                first, second
+            ))
+            .context("prepare results query failed")?;
+        let records = statement
+            .query_map(
+                params_from_iter(
+                    [Value::Text(game.slug().to_string())]
+                        .into_iter()
+                        .chain(values)
+                        .chain([Value::Integer(limit as i64)]),
+                ),
+                Record::from_row,
+            )
+            .context("query results failed")?
+            .collect::<rusqlite::Result<Vec<Record>>>()
+            .context("read results failed")?;
+
+        Ok(records)
+    }
+
+    pub fn form(&self, game: Game, team: &str, limit: usize) -> Result<Form> {
+        let connection = self.connection()?;
+        let side = Self::side(&connection, game, team)?;
+        let records = Self::query_results(&connection, game, &side, limit)?;
+
+        Ok(Form {
+            team: spelled(&records, &side, team),
+            results: records.iter().map(|record| record.outcome(&side)).collect(),
+        })
+    }
+
+    pub fn head_to_head(
+        &self,
+        game: Game,
+        team1: &str,
+        team2: &str,
+        limit: usize,
+    ) -> Result<HeadToHead> {
+        let connection = self.connection()?;
+        let side1 = Self::side(&connection, game, team1)?;
+        let side2 = Self::side(&connection, game, team2)?;
+        let (first1, mut values) = side1.condition("team1");
+        let (second2, more) = side2.condition("team2");
+        values.extend(more);
+        let (first2, more) = side2.condition("team1");
+        values.extend(more);
+        let (second1, more) = side1.condition("team2");
+        values.extend(more);
+        let mut statement = connection
+            .prepare(&format!(
+                "SELECT * FROM matches WHERE game = ? AND status = 'finished' \
+                 AND (({} AND {}) OR ({} AND {})) ORDER BY start_time DESC LIMIT ?",
+                first1, second2, first2, second1
+            ))
+            .context("prepare head to head query failed")?;
+        let records = statement
+            .query_map(
+                params_from_iter(
+                    [Value::Text(game.slug().to_string())]
+                        .into_iter()
+                        .chain(values)
+                        .chain([Value::Integer(limit as i64)]),
+                ),
+                Record::from_row,
+            )
+            .context("query head to head failed")?
+            .collect::<rusqlite::Result<Vec<Record>>>()
+            .context("read head to head failed")?;
+        let wins = |side: &Side| {
+            records
+                .iter()
+                .filter(|record| record.outcome(side).won == Some(true))
+                .count()
+        };
+
+        Ok(HeadToHead {
+            team1: spelled(&records, &side1, team1),
+            team2: spelled(&records, &side2, team2),
+            wins1: wins(&side1),
+            wins2: wins(&side2),
+            records,
+        })
+    }
+}
+
+/// The name of `side` as the latest record spells it, `fallback` without records.
+fn spelled(records: &[Record], side: &Side, fallback: &str) -> String {
+    records
+        .first()
+        .map(|record| record.name(side).to_string())
+        .unwrap_or(fallback.to_string())
+}
+
+/// Ids of the matches archived by another source for the same teams around the
+/// start of `item`.
+fn duplicates(connection: &Connection, game: Game, item: &Match) -> Result<Vec<String>> {
+    let mut statement = connection.prepare(
+        "SELECT id, team1_id, team1, team2_id, team2 FROM matches WHERE game = ?1 \
+         AND id <> ?2 AND start_time BETWEEN ?3 AND ?4",
+    )?;
+    let start = item.info.start_time;
+    let rows = statement
+        .query_map(
+            params![
+                game.slug(),
+                item.id,
+                start - SAME_MATCH_TOLERANCE,
+                start + SAME_MATCH_TOLERANCE
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    (row.get::<_, i32>(1)?, row.get::<_, String>(2)?),
+                    (row.get::<_, i32>(3)?, row.get::<_, String>(4)?),
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let team1 = (item.team1.id, item.team1.name.as_str());
+    let team2 = (item.team2.id, item.team2.name.as_str());
+
+    Ok(rows
+        .into_iter()
+        .filter(|(_, (id1, name1), (id2, name2))| {
+            let (other1, other2) = ((*id1, name1.as_str()), (*id2, name2.as_str()));
+            (is_same_team(team1, other1) && is_same_team(team2, other2))
+                || (is_same_team(team1, other2) && is_same_team(team2, other1))
+        })
+        .map(|(id, _, _)| id)
+        .collect())
+}
+
+/// Saves the matches of `window` into the archive, so results are kept even when
+/// nothing is notified about them.
+#[derive(Clone)]
+pub struct Archiver {
+    game: Game,
+    provider: Arc<dyn MatchProvider>,
+    archive: Archive,
+    window: Window,
+}
+
+impl Archiver {
+    pub fn new(
+        game: Game,
+        provider: Arc<dyn MatchProvider>,
+        archive: Archive,
+        window: Window,
+    ) -> Archiver {
+        Self {
+            game,
+            provider,
+            archive,
+            window,
+        }
+    }
+}
+
+#[async_trait]
+impl Task for Archiver {
+    async fn run(&mut self) -> Result<()> {
+        info!("run task `{} archive`", self.game.slug().green().bold());
+
+        // saving only adds and updates, so a degraded fetch is fine
+        let Fetched { matches, degraded } = self.provider.fetch(&self.window.dates()).await?;
+        if degraded {
+            warn!(
+                "some {} providers failed, archive the other matches",
+                self.game
+            );
+        }
+        let count = self
+            .archive
+            .save(self.game, &matches)
+            .context("archive matches failed")?;
+        info!(
+            "archive {} matches {}",
+            count,
+            "successfully".green().bold()
+        );
+
+        Ok(())
+    }
+}
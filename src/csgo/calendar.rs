@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+
+use crate::csgo::api::Match;
+use crate::csgo::filter::Filter;
+use crate::csgo::game::Game;
+use crate::csgo::provider::{keep_missing, Fetched, MatchProvider};
+use crate::utils::{Calendar, Content, Env, Task, Window};
+
+const DEFAULT_PAST_DAYS: u64 = 1;
+const DEFAULT_FUTURE_DAYS: u64 = 14;
+
+/// Refreshes the iCalendar feed of followed matches served over http.
+#[derive(Clone)]
+pub struct CsgoCalendar {
+    game: Game,
+    provider: Arc<dyn MatchProvider>,
+    window: Window,
+    filter: Filter,
+    content: Content,
+    /// Matches of the published calendar.
+    matches: Vec<Match>,
+}
+
+impl CsgoCalendar {
+    pub fn new(game: Game, provider: Arc<dyn MatchProvider>, window: Window) -> CsgoCalendar {
+        Self {
+            game,
+            provider,
+            window,
+            filter: Filter::all(),
+            content: Content::default(),
+            matches: Vec::new(),
+        }
+    }
+
+    /// Reads `CSGO_CALENDAR_FILTER`, falling back to `CSGO_FILTER`.
+    pub fn from_env(game: Game, provider: Arc<dyn MatchProvider>) -> Result<CsgoCalendar> {
+        let filter = Filter::from_env_for(game, Some("CALENDAR")).context("init filter failed")?;
+        let window = Window::from_env(
+            &Env::new("CALENDAR"),
+            DEFAULT_PAST_DAYS,
+            DEFAULT_FUTURE_DAYS,
+        )?;
+
+        Ok(Self::new(game, provider, window).with_filter(filter))
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+
+        self
+    }
+
+    pub fn content(&self) -> Content {
+        self.content.clone()
+    }
+}
+
+#[async_trait]
+impl Task for CsgoCalendar {
+    async fn run(&mut self) -> Result<()> {
+        info!(
+            "run task `{}`",
+            format!("{} calendar", self.game).green().bold()
+        );
+
+        let Fetched {
+            mut matches,
+            degraded,
+        } = self.provider.fetch(&self.window.dates()).await?;
+        matches.retain(|item| self.filter.matches(item));
+        // subscribers would drop the events missing from a partial feed
+        if degraded {
+            warn!(
+                "some {} providers failed, keep their matches in calendar",
+                self.game
+            );
+            let window = &self.window;
+            keep_missing(
+                &mut matches,
+                self.matches
+                    .drain(..)
+                    .filter(|item| window.contains(item.info.start_time)),
+            );
+        }
+
+        let calendar = Calendar::new(format!("{} matches", self.game))
+            .with_events(matches.iter().map(Match::event));
+        *self.content.write().await = Some(calendar.render());
+        info!(
+            "refresh calendar with {} matches {}",
+            matches.len(),
+            "successfully".green().bold()
+        );
+        self.matches = matches;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::csgo::api::{Info, Match};
+use crate::csgo::provider::is_same;
+use crate::utils::Window;
+
+/// Followed matches of the last notification, keyed by match id.
+pub type Snapshot = BTreeMap<String, Match>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    New,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    #[serde(rename = "match")]
+    pub item: Match,
+    /// Info before the change, only for changed matches.
+    pub before: Option<Info>,
+}
+
+/// Compares `matches` with `snapshot`, matches of the snapshot outside of `window` are
+/// ignored as they are simply not fetched anymore. A match another source reported
+/// under its own id is known already.
+pub fn diff(snapshot: &Snapshot, matches: &[Match], window: &Window) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for item in matches {
+        match snapshot.get(&item.id) {
+            None if snapshot.values().any(|previous| is_same(previous, item)) => {}
+            None => changes.push(Change {
+                kind: ChangeKind::New,
+                item: item.clone(),
+                before: None,
+            }),
+            Some(previous)
+                if previous.info.start_time != item.info.start_time
+                    || previous.info.bo != item.info.bo =>
+            {
+                changes.push(Change {
+                    kind: ChangeKind::Changed,
+                    item: item.clone(),
+                    before: Some(previous.info.clone()),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for previous in snapshot.values() {
+        let in_window = window.contains(previous.info.start_time);
+        if in_window && !matches.iter().any(|item| is_same(item, previous)) {
+            changes.push(Change {
+                kind: ChangeKind::Removed,
+                item: previous.clone(),
+                before: None,
+            });
+        }
+    }
+
+    changes
+}
+
+pub fn snapshot(matches: &[Match]) -> Snapshot {
+    matches
+        .iter()
+        .map(|item| (item.id.clone(), item.clone()))
+        .collect()
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, Proxy};
+use tokio::sync::Mutex;
+use tokio::time::{self, Instant};
+
+use crate::csgo::error::ApiError;
+use crate::utils::Env;
+
+const DEFAULT_USER_AGENT: &str = concat!("iknow/", env!("CARGO_PKG_VERSION"));
+const DEFAULT_CONNECT_TIMEOUT: u64 = 10;
+const DEFAULT_TIMEOUT: u64 = 30;
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_RETRY_BACKOFF: u64 = 500;
+const DEFAULT_RATE_INTERVAL: u64 = 0;
+
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    pub user_agent: String,
+    pub connect_timeout: Duration,
+    pub timeout: Duration,
+    pub retries: u32,
+    pub retry_backoff: Duration,
+    /// Minimal time between two requests, zero disables the limit.
+    pub rate_interval: Duration,
+    /// `http://`, `https://`, `socks5://` or `socks5h://` proxy for every request.
+    pub proxy: Option<String>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT),
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT),
+            retries: DEFAULT_RETRIES,
+            retry_backoff: Duration::from_millis(DEFAULT_RETRY_BACKOFF),
+            rate_interval: Duration::from_millis(DEFAULT_RATE_INTERVAL),
+            proxy: None,
+        }
+    }
+}
+
+impl ClientOptions {
+    pub fn from_env(env: &Env) -> Result<ClientOptions> {
+        Ok(Self {
+            user_agent: env
+                .var("USER_AGENT")
+                .unwrap_or(DEFAULT_USER_AGENT.to_string()),
+            connect_timeout: Duration::from_secs(
+                env.parse("CONNECT_TIMEOUT", DEFAULT_CONNECT_TIMEOUT)?,
+            ),
+            timeout: Duration::from_secs(env.parse("TIMEOUT", DEFAULT_TIMEOUT)?),
+            retries: env.parse("RETRIES", DEFAULT_RETRIES)?,
+            retry_backoff: Duration::from_millis(
+                env.parse("RETRY_BACKOFF", DEFAULT_RETRY_BACKOFF)?,
+            ),
+            rate_interval: Duration::from_millis(
+                env.parse("RATE_INTERVAL", DEFAULT_RATE_INTERVAL)?,
+            ),
+            proxy: env.var("PROXY"),
+        })
+    }
+}
+
+/// Http client retrying failed requests and spacing requests out.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+    retries: u32,
+    retry_backoff: Duration,
+    rate_interval: Duration,
+    next: Arc<Mutex<Instant>>,
+}
+
+impl HttpClient {
+    pub fn new(options: ClientOptions) -> Result<HttpClient> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip, deflate, br"),
+        );
+        let mut builder = Client::builder()
+            .default_headers(headers)
+            .user_agent(options.user_agent)
+            .connect_timeout(options.connect_timeout)
+            .timeout(options.timeout);
+        if let Some(proxy) = options.proxy {
+            let proxy =
+                Proxy::all(&proxy).with_context(|| format!("parse proxy `{}` failed", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+        let client = builder.build().context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            retries: options.retries,
+            retry_backoff: options.retry_backoff,
+            rate_interval: options.rate_interval,
+            next: Arc::new(Mutex::new(Instant::now())),
+        })
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Vec<u8>, ApiError> {
+        self.get_with_headers(url, HeaderMap::new()).await
+    }
+
+    /// Retries connection errors, timeouts and 5xx responses with exponential backoff.
+    pub async fn get_with_headers(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<Vec<u8>, ApiError> {
+        let mut attempt = 0;
+        loop {
+            let result = self.try_get(url, headers.clone()).await;
+            let retry = match &result {
+                Err(ApiError::Network(e)) => e.is_connect() || e.is_timeout(),
+                Err(ApiError::Status(status)) => status.is_server_error(),
+                _ => false,
+            };
+            if !retry || attempt >= self.retries {
+                return result;
+            }
+
+            let backoff = self.retry_backoff * 2u32.saturating_pow(attempt);
+            attempt += 1;
+            warn!(
+                "request `{}` failed, retry {}/{} in {:?}",
+                url, attempt, self.retries, backoff
+            );
+            time::sleep(backoff).await;
+        }
+    }
+
+    async fn try_get(&self, url: &str, headers: HeaderMap) -> Result<Vec<u8>, ApiError> {
+        self.wait().await;
+        let response = self
+            .client
+            .get(url)
+            .headers(headers)
+            .send()
+            .await
+            .map_err(ApiError::Network)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ApiError::Status(status));
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|body| body.to_vec())
+            .map_err(ApiError::Network)
+    }
+
+    async fn wait(&self) {
+        if self.rate_interval.is_zero() {
+            return;
+        }
+
+        // holding the lock while sleeping queues the other requests behind this one
+        let mut next = self.next.lock().await;
+        time::sleep_until(*next).await;
+        *next = Instant::now() + self.rate_interval;
+    }
+}
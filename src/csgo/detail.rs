@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VetoKind {
+    Ban,
+    Pick,
+    Decider,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Veto {
+    pub map: String,
+    pub team_id: Option<i32>,
+    pub kind: VetoKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapScore {
+    pub map: String,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub winner: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub name: String,
+    pub team_id: Option<i32>,
+    pub kills: Option<i32>,
+    pub deaths: Option<i32>,
+    pub assists: Option<i32>,
+    pub adr: Option<f64>,
+    pub rating: Option<f64>,
+}
+
+/// Map veto, map scores and player performance of a match, as far as the api reports them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Detail {
+    pub vetoes: Vec<Veto>,
+    pub maps: Vec<MapScore>,
+    pub players: Vec<PlayerStats>,
+}
+
+impl Detail {
+    pub(super) fn new(
+        map_bpdtos: Option<Vec<MapBpDto>>,
+        single_match_data_dtos: Option<Vec<SingleMatchDataDto>>,
+        stats: Option<Vec<PlayerStatsDto>>,
+        detail: Option<MatchDetailDto>,
+    ) -> Option<Detail> {
+        let detail = detail.unwrap_or_default();
+        let vetoes = map_bpdtos
+            .or(detail.map_bpdtos)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(MapBpDto::into_veto)
+            .collect::<Vec<Veto>>();
+        let maps = single_match_data_dtos
+            .or(detail.single_match_data_dtos)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(SingleMatchDataDto::into_map_score)
+            .collect::<Vec<MapScore>>();
+        let players = stats
+            .or(detail.performance_stats_list)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(PlayerStatsDto::into_player_stats)
+            .collect::<Vec<PlayerStats>>();
+
+        if vetoes.is_empty() && maps.is_empty() && players.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            vetoes,
+            maps,
+            players,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+pub(super) struct MapBpDto {
+    pub id: Option<i32>,
+    #[serde(alias = "map")]
+    pub map_name: Option<String>,
+    pub team_id: Option<i32>,
+    /// `1` for a ban, `2` for a pick and `3` for the decider.
+    #[serde(rename = "type", alias = "bpType")]
+    pub bp_type: Option<i32>,
+    pub sort: Option<i32>,
+}
+
+impl MapBpDto {
+    fn into_veto(self) -> Option<Veto> {
+        let kind = match self.bp_type? {
+            1 => VetoKind::Ban,
+            2 => VetoKind::Pick,
+            3 => VetoKind::Decider,
+            _ => return None,
+        };
+
+        Some(Veto {
+            map: self.map_name?,
+            team_id: self.team_id,
+            kind,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+pub(super) struct SingleMatchDataDto {
+    pub id: Option<i32>,
+    #[serde(alias = "map")]
+    pub map_name: Option<String>,
+    pub score1: Option<i32>,
+    pub score2: Option<i32>,
+    pub winner_team_id: Option<i32>,
+    pub status: Option<i32>,
+    pub sort: Option<i32>,
+}
+
+impl SingleMatchDataDto {
+    fn into_map_score(self) -> Option<MapScore> {
+        Some(MapScore {
+            map: self.map_name?,
+            score1: self.score1,
+            score2: self.score2,
+            winner: self.winner_team_id,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+pub(super) struct PlayerStatsDto {
+    pub player_id: Option<i32>,
+    #[serde(alias = "nickName", alias = "playerName")]
+    pub nickname: Option<String>,
+    pub team_id: Option<i32>,
+    pub kills: Option<i32>,
+    pub deaths: Option<i32>,
+    pub assists: Option<i32>,
+    pub adr: Option<f64>,
+    pub rating: Option<f64>,
+}
+
+impl PlayerStatsDto {
+    fn into_player_stats(self) -> Option<PlayerStats> {
+        Some(PlayerStats {
+            name: self.nickname?,
+            team_id: self.team_id,
+            kills: self.kills,
+            deaths: self.deaths,
+            assists: self.assists,
+            adr: self.adr,
+            rating: self.rating,
+        })
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct MatchDetailDto {
+    #[serde(rename = "mapBPDTOS")]
+    pub map_bpdtos: Option<Vec<MapBpDto>>,
+    #[serde(rename = "singleMatchDataDTOS")]
+    pub single_match_data_dtos: Option<Vec<SingleMatchDataDto>>,
+    pub performance_stats_list: Option<Vec<PlayerStatsDto>>,
+}
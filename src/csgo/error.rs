@@ -0,0 +1,14 @@
+use reqwest::StatusCode;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("request failed")]
+    Network(#[source] reqwest::Error),
+    #[error("server responded with status {0}")]
+    Status(StatusCode),
+    #[error("api responded with code {code}: {message}")]
+    Envelope { code: i32, message: String },
+    #[error("decode response failed")]
+    Decode(#[source] serde_json::Error),
+}
@@ -0,0 +1,337 @@
+//! Filter expressions deciding which matches are followed, for example
+//! `team or (important and bo >= 3) or event ~ "major"`.
+//!
+//! Terms are `all`, `team` (a followed team plays), `hot`, `important`,
+//! `star <op> N`, `bo <op> N`, `rank <op> N` (both teams are ranked and
+//! compare, so `rank <= 20` means two top 20 teams) and `event ~ "pattern"`
+//! (case-insensitive, `*` matches anything), combined with `and`, `or`, `not`
+//! and parentheses.
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::csgo::api::Match;
+use crate::csgo::game::Game;
+use crate::csgo::watchlist::Watchlist;
+
+const DEFAULT_FILTER: &str = "team";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn compare(self, left: i64, right: i64) -> bool {
+        match self {
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Op(Op),
+    Tilde,
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    All,
+    Team,
+    Hot,
+    Important,
+    Star(Op, i64),
+    Bo(Op, i64),
+    Rank(Op, i64),
+    Event(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    expr: Expr,
+    watchlist: Watchlist,
+}
+
+impl Filter {
+    pub fn parse(text: &str, watchlist: Watchlist) -> Result<Filter> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, index: 0 };
+        let expr = parser.or()?;
+        if let Some(token) = parser.peek() {
+            bail!("unexpected `{:?}`", token);
+        }
+
+        Ok(Self { expr, watchlist })
+    }
+
+    /// Lets every match through.
+    pub fn all() -> Filter {
+        Self {
+            expr: Expr::All,
+            watchlist: Watchlist::default(),
+        }
+    }
+
+    pub fn from_env(game: Game) -> Result<Filter> {
+        Self::from_env_for(game, None)
+    }
+
+    /// Reads the filter of `task` like `CSGO_LIVE_FILTER`, falling back to `CSGO_FILTER`.
+    pub fn from_env_for(game: Game, task: Option<&str>) -> Result<Filter> {
+        let watchlist = Watchlist::from_env(game).context("init watchlist failed")?;
+        let env = game.env();
+        let filter = task
+            .and_then(|task| env.var(&format!("{}_FILTER", task.to_uppercase())))
+            .or_else(|| env.var("FILTER"))
+            .unwrap_or(DEFAULT_FILTER.to_string());
+
+        Self::parse(&filter, watchlist).with_context(|| format!("parse filter `{}` failed", filter))
+    }
+
+    pub fn matches(&self, item: &Match) -> bool {
+        self.eval(&self.expr, item)
+    }
+
+    fn eval(&self, expr: &Expr, item: &Match) -> bool {
+        match expr {
+            Expr::All => true,
+            Expr::Team => {
+                self.watchlist.contains(item.team1.id, &item.team1.name)
+                    || self.watchlist.contains(item.team2.id, &item.team2.name)
+            }
+            Expr::Hot => item.info.hot,
+            Expr::Important => item.info.important,
+            Expr::Star(op, value) => op.compare(item.info.star as i64, *value),
+            Expr::Bo(op, value) => item
+                .info
+                .maps()
+                .map(|maps| op.compare(maps, *value))
+                .unwrap_or(false),
+            Expr::Rank(op, value) => [&item.team1, &item.team2].into_iter().all(|team| {
+                team.rank
+                    .map(|rank| op.compare(rank as i64, *value))
+                    .unwrap_or(false)
+            }),
+            Expr::Event(pattern) => glob(pattern, &item.info.name.to_lowercase()),
+            Expr::Not(expr) => !self.eval(expr, item),
+            Expr::And(left, right) => self.eval(left, item) && self.eval(right, item),
+            Expr::Or(left, right) => self.eval(left, item) || self.eval(right, item),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => bail!("missing `)`"),
+                }
+            }
+            Some(Token::Word(word)) => self.term(&word.to_lowercase()),
+            Some(token) => bail!("unexpected `{:?}`", token),
+            None => bail!("unexpected end of filter"),
+        }
+    }
+
+    fn term(&mut self, word: &str) -> Result<Expr> {
+        let expr = match word {
+            "all" => Expr::All,
+            "team" => Expr::Team,
+            "hot" => Expr::Hot,
+            "important" => Expr::Important,
+            "star" => {
+                let (op, value) = self.comparison(word)?;
+                Expr::Star(op, value)
+            }
+            "bo" => {
+                let (op, value) = self.comparison(word)?;
+                Expr::Bo(op, value)
+            }
+            "rank" => {
+                let (op, value) = self.comparison(word)?;
+                Expr::Rank(op, value)
+            }
+            "event" => {
+                match self.next() {
+                    Some(Token::Tilde) | Some(Token::Op(Op::Eq)) => {}
+                    _ => bail!("`event` should be followed by `~`"),
+                }
+                match self.next() {
+                    Some(Token::Text(pattern)) | Some(Token::Word(pattern)) => {
+                        Expr::Event(pattern.to_lowercase())
+                    }
+                    _ => bail!("`event ~` should be followed by a pattern"),
+                }
+            }
+            _ => bail!("unknown term `{}`", word),
+        };
+
+        Ok(expr)
+    }
+
+    fn comparison(&mut self, word: &str) -> Result<(Op, i64)> {
+        let Some(Token::Op(op)) = self.next() else {
+            bail!("`{}` should be followed by a comparison", word);
+        };
+        let Some(Token::Word(value)) = self.next() else {
+            bail!("`{}` should be compared with a number", word);
+        };
+        let value = value
+            .parse::<i64>()
+            .with_context(|| format!("parse number `{}` failed", value))?;
+
+        Ok((op, value))
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let token = match ch {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '~' => Token::Tilde,
+            '"' => {
+                let mut content = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => content.push(c),
+                        None => return Err(anyhow!("missing closing `\"`")),
+                    }
+                }
+                Token::Text(content)
+            }
+            '=' | '!' | '<' | '>' => {
+                let eq = chars.next_if_eq(&'=').is_some();
+                let op = match (ch, eq) {
+                    ('=', _) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    _ => bail!("unknown operator `{}`", ch),
+                };
+                Token::Op(op)
+            }
+            _ => {
+                let mut word = ch.to_string();
+                while let Some(c) =
+                    chars.next_if(|c| !c.is_whitespace() && !"()~\"=!<>".contains(*c))
+                {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Matches `text` against `pattern` where `*` matches anything, a pattern without `*`
+/// only has to be contained in `text`.
+fn glob(pattern: &str, text: &str) -> bool {
+    if !pattern.contains('*') {
+        return text.contains(pattern);
+    }
+
+    let parts = pattern.split('*').collect::<Vec<&str>>();
+    let mut rest = text;
+    for (index, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if index == 0 {
+            let Some(stripped) = rest.strip_prefix(part) else {
+                return false;
+            };
+            rest = stripped;
+        } else if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            let Some(position) = rest.find(part) else {
+                return false;
+            };
+            rest = &rest[position + part.len()..];
+        }
+    }
+
+    true
+}
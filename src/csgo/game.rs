@@ -0,0 +1,67 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Env;
+
+/// A title served by the event center, `csgo` settings keep their `CSGO_` prefix and the
+/// other games read theirs from `DOTA2_`, `LOL_` and `VALORANT_`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Game {
+    #[default]
+    Csgo,
+    Dota2,
+    Lol,
+    Valorant,
+}
+
+impl Game {
+    pub const ALL: [Game; 4] = [Game::Csgo, Game::Dota2, Game::Lol, Game::Valorant];
+
+    /// Path segment in the event center, also used for tags, ids and file names.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Game::Csgo => "csgo",
+            Game::Dota2 => "dota2",
+            Game::Lol => "lol",
+            Game::Valorant => "valorant",
+        }
+    }
+
+    pub fn env(&self) -> Env {
+        Env::new(self.slug().to_uppercase())
+    }
+
+    pub fn liquipedia_wiki(&self) -> &'static str {
+        match self {
+            Game::Csgo => "counterstrike",
+            Game::Dota2 => "dota2",
+            Game::Lol => "leagueoflegends",
+            Game::Valorant => "valorant",
+        }
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.slug())
+    }
+}
+
+impl FromStr for Game {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let text = text.trim().to_lowercase();
+        match Game::ALL.into_iter().find(|game| game.slug() == text) {
+            Some(game) => Ok(game),
+            None => bail!(
+                "unknown game `{}`, should be one of csgo, dota2, lol, valorant",
+                text
+            ),
+        }
+    }
+}
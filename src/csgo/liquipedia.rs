@@ -0,0 +1,376 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::csgo::api::{Info, Match, Player, State, Status, Team};
+use crate::csgo::client::{ClientOptions, HttpClient};
+use crate::csgo::error::ApiError;
+use crate::csgo::game::Game;
+use crate::csgo::provider::MatchProvider;
+use crate::csgo::stream::{Stream, StreamUrls};
+use crate::utils::Env;
+
+const DEFAULT_API_URL: &str = "https://api.liquipedia.net";
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const LIMIT: usize = 500;
+
+/// Matches from the Liquipedia database api, dates there are in UTC.
+#[derive(Debug, Clone)]
+pub struct Liquipedia {
+    client: HttpClient,
+    game: Game,
+    base_url: String,
+    api_key: String,
+    wiki: String,
+    streams: StreamUrls,
+}
+
+impl Liquipedia {
+    pub fn new(api_key: impl Into<String>, options: ClientOptions) -> Result<Liquipedia> {
+        let client = HttpClient::new(options).context("init http client failed")?;
+
+        Ok(Self {
+            client,
+            game: Game::default(),
+            base_url: DEFAULT_API_URL.to_string(),
+            api_key: api_key.into(),
+            wiki: Game::default().liquipedia_wiki().to_string(),
+            streams: StreamUrls::default(),
+        })
+    }
+
+    pub fn from_env(game: Game) -> Result<Option<Liquipedia>> {
+        let env = Env::new("LIQUIPEDIA");
+        let Some(api_key) = env.var("API_KEY") else {
+            return Ok(None);
+        };
+        let options = ClientOptions::from_env(&env).context("init client options failed")?;
+        let streams = StreamUrls::from_env().context("init stream urls failed")?;
+        let mut liquipedia = Self::new(api_key, options)?
+            .with_game(game)
+            .with_streams(streams);
+        if let Some(base_url) = env.var("API_URL") {
+            liquipedia = liquipedia.with_base_url(base_url);
+        }
+        // `LIQUIPEDIA_WIKI` predates other games, so it only overrides the csgo wiki
+        if let Some(wiki) = env.var("WIKI") {
+            match game {
+                Game::Csgo => liquipedia = liquipedia.with_wiki(wiki),
+                _ => warn!(
+                    "`LIQUIPEDIA_WIKI` only applies to csgo, ignored for {}",
+                    game
+                ),
+            }
+        }
+
+        Ok(Some(liquipedia))
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+
+        self
+    }
+
+    /// Also switches to the wiki of `game`.
+    pub fn with_game(mut self, game: Game) -> Self {
+        self.game = game;
+        self.wiki = game.liquipedia_wiki().to_string();
+
+        self
+    }
+
+    pub fn with_wiki(mut self, wiki: impl Into<String>) -> Self {
+        self.wiki = wiki.into();
+
+        self
+    }
+
+    pub fn with_streams(mut self, streams: StreamUrls) -> Self {
+        self.streams = streams;
+
+        self
+    }
+
+    async fn get_list(&self, start: i64, end: i64) -> Result<Vec<LpdbMatch>> {
+        let format = |timestamp: i64| {
+            chrono::DateTime::from_timestamp(timestamp, 0)
+                .unwrap_or_default()
+                .format(DATE_FORMAT)
+                .to_string()
+        };
+        let conditions = format!(
+            "[[date::>{}]] AND [[date::<{}]]",
+            format(start - 1),
+            format(end)
+        );
+        let limit = LIMIT.to_string();
+        let url = Url::parse_with_params(
+            &format!("{}/api/v3/match", self.base_url),
+            [
+                ("wiki", self.wiki.as_str()),
+                ("conditions", conditions.as_str()),
+                ("order", "date ASC"),
+                ("limit", limit.as_str()),
+            ],
+        )
+        .with_context(|| format!("parse api url `{}` failed", self.base_url))?;
+        let mut headers = HeaderMap::new();
+        if let Ok(value) = HeaderValue::from_str(&format!("Apikey {}", self.api_key)) {
+            headers.insert(header::AUTHORIZATION, value);
+        }
+
+        let body = self.client.get_with_headers(url.as_str(), headers).await?;
+        let response = serde_json::from_slice::<LpdbResponse>(&body).map_err(ApiError::Decode)?;
+        if !response.error.is_empty() {
+            return Err(ApiError::Envelope {
+                code: 0,
+                message: response.error.join("; "),
+            }
+            .into());
+        }
+
+        // a malformed item should not cost the whole range
+        let list = response
+            .result
+            .into_iter()
+            .filter_map(|item| {
+                let id = item.get("match2id").cloned().unwrap_or_default();
+                match serde_json::from_value::<LpdbMatch>(item) {
+                    Ok(item) => Some(item),
+                    Err(e) => {
+                        warn!("skip malformed liquipedia match `{}`: {}", id, e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<LpdbMatch>>();
+
+        Ok(list)
+    }
+}
+
+#[async_trait]
+impl MatchProvider for Liquipedia {
+    fn name(&self) -> &str {
+        "liquipedia"
+    }
+
+    async fn get_matches_by_dates(&self, dates: &[NaiveDate]) -> Result<Vec<Match>> {
+        let (Some(first), Some(last)) = (dates.iter().min(), dates.iter().max()) else {
+            return Ok(Vec::new());
+        };
+        let start_of = |date: NaiveDate| {
+            Local
+                .from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .earliest()
+                .map(|time| time.timestamp())
+                .unwrap_or_default()
+        };
+        let start = start_of(*first);
+        let end = start_of(last.checked_add_days(Days::new(1)).unwrap_or(*last));
+
+        let dates = dates.iter().collect::<HashSet<&NaiveDate>>();
+        let matches = self
+            .get_list(start, end)
+            .await
+            .context("get liquipedia matches failed")?
+            .into_iter()
+            .filter_map(|item| item.into_match(self.game, &self.streams))
+            .filter(|item| {
+                Local
+                    .timestamp_opt(item.info.start_time, 0)
+                    .single()
+                    .is_some_and(|time| dates.contains(&time.date_naive()))
+            })
+            .collect::<Vec<Match>>();
+
+        Ok(matches)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LpdbResponse {
+    #[serde(default)]
+    result: Vec<serde_json::Value>,
+    #[serde(default)]
+    error: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LpdbMatch {
+    match2id: String,
+    date: String,
+    #[serde(default)]
+    bestof: i32,
+    #[serde(default)]
+    tournament: String,
+    #[serde(default)]
+    liquipediatier: String,
+    #[serde(default)]
+    finished: i32,
+    #[serde(default)]
+    winner: String,
+    #[serde(default)]
+    match2opponents: Vec<LpdbOpponent>,
+    /// Channels by platform like `{"twitch": "esl_csgo"}`, an empty list when there are none.
+    #[serde(default)]
+    stream: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct LpdbOpponent {
+    name: String,
+    /// `-1` until the match has a score.
+    #[serde(default)]
+    score: Option<i32>,
+    #[serde(default)]
+    teamtemplate: Option<LpdbTeamTemplate>,
+    #[serde(default)]
+    match2players: Vec<LpdbPlayer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LpdbPlayer {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    displayname: String,
+    #[serde(default)]
+    flag: String,
+}
+
+impl LpdbPlayer {
+    fn into_player(self) -> Option<Player> {
+        let name = match self.displayname.is_empty() {
+            true => self.name,
+            false => self.displayname,
+        };
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(Player {
+            name,
+            real_name: None,
+            country: Some(self.flag).filter(|flag| !flag.is_empty()),
+            avatar: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LpdbTeamTemplate {
+    #[serde(default)]
+    imageurl: Option<String>,
+}
+
+impl LpdbMatch {
+    /// Matches without two named opponents yet are left out.
+    fn into_match(self, game: Game, streams: &StreamUrls) -> Option<Match> {
+        let start_time = NaiveDateTime::parse_from_str(&self.date, DATE_FORMAT)
+            .ok()?
+            .and_utc()
+            .timestamp();
+        let mut opponents = self.match2opponents.into_iter();
+        let (opponent1, opponent2) = (opponents.next()?, opponents.next()?);
+        if opponent1.name.is_empty() || opponent2.name.is_empty() {
+            return None;
+        }
+
+        let score = |opponent: &LpdbOpponent| opponent.score.filter(|score| *score >= 0);
+        let (score1, score2) = (score(&opponent1), score(&opponent2));
+        let winner_side = match self.winner.as_str() {
+            "1" => Some(1),
+            "2" => Some(2),
+            _ => None,
+        };
+        let status = match (self.finished, score1.or(score2)) {
+            (1, _) => Status::Finished,
+            (_, Some(_)) => Status::Live,
+            _ => Status::Upcoming,
+        };
+        // ids of liquipedia are not team ids, sharing the field would mix up teams
+        let team = |opponent: LpdbOpponent| Team {
+            id: 0,
+            name: opponent.name,
+            logo: opponent
+                .teamtemplate
+                .and_then(|template| template.imageurl)
+                .unwrap_or_default(),
+            rank: None,
+            location: None,
+            players: opponent
+                .match2players
+                .into_iter()
+                .filter_map(LpdbPlayer::into_player)
+                .collect(),
+        };
+        let star = match self.liquipediatier.as_str() {
+            "1" => 3,
+            "2" => 2,
+            "3" => 1,
+            _ => 0,
+        };
+
+        Some(Match {
+            id: format!("liquipedia-{}", self.match2id),
+            game,
+            team1: team(opponent1),
+            team2: team(opponent2),
+            info: Info {
+                start_time,
+                bo: match self.bestof {
+                    0 => String::new(),
+                    bestof => format!("bo{}", bestof),
+                },
+                name: self.tournament,
+                star,
+                hot: false,
+                important: star == 3,
+                prize: None,
+                stage: None,
+            },
+            state: State {
+                status,
+                score1,
+                score2,
+                winner: None,
+                winner_side,
+            },
+            detail: None,
+            stream: stream(&self.stream, streams),
+            tournament: None,
+        })
+    }
+}
+
+/// Picks the first channel of a known platform, keys like `twitch_en_2` count as `twitch`.
+fn stream(channels: &serde_json::Value, streams: &StreamUrls) -> Option<Stream> {
+    let channels = channels
+        .as_object()?
+        .iter()
+        .filter_map(|(key, room)| {
+            let platform = key
+                .split('_')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(|c: char| c.is_ascii_digit());
+            Stream::new(platform, room.as_str()?)
+        })
+        .collect::<Vec<Stream>>();
+    let position = channels
+        .iter()
+        .position(|stream| streams.knows(&stream.platform))
+        .unwrap_or_default();
+
+    channels
+        .into_iter()
+        .nth(position)
+        .map(|stream| streams.resolve(stream))
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Local;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::csgo::api::{Match, Status};
+use crate::csgo::filter::Filter;
+use crate::csgo::game::Game;
+use crate::csgo::provider::{keep_missing, Fetched, MatchProvider};
+use crate::notify::{Notice, Notify, Priority};
+use crate::utils::{Task, Window};
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum LiveKind {
+    Live,
+    Score,
+    Finished,
+}
+
+/// Follows matches of today, polling the api on every run only while a followed
+/// match is about to start or in progress, and every `refresh` seconds otherwise.
+#[derive(Clone)]
+pub struct CsgoLive {
+    game: Game,
+    provider: Arc<dyn MatchProvider>,
+    notifier: Arc<dyn Notify>,
+    lead: i64,
+    refresh: i64,
+    filter: Filter,
+    last_refresh: i64,
+    matches: HashMap<String, Match>,
+}
+
+impl CsgoLive {
+    /// `lead` and `refresh` are in seconds.
+    pub fn new(
+        game: Game,
+        provider: Arc<dyn MatchProvider>,
+        notifier: Arc<dyn Notify>,
+        lead: i64,
+        refresh: i64,
+    ) -> CsgoLive {
+        Self {
+            game,
+            provider,
+            notifier,
+            lead,
+            refresh,
+            filter: Filter::all(),
+            last_refresh: 0,
+            matches: HashMap::new(),
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> CsgoLive {
+        self.filter = filter;
+
+        self
+    }
+
+    fn is_active(&self, now: i64) -> bool {
+        self.matches.values().any(|item| match item.state.status {
+            Status::Live => true,
+            Status::Upcoming => item.info.start_time - self.lead <= now,
+            Status::Finished => false,
+        })
+    }
+
+    async fn notify(&self, kind: LiveKind, item: &Match) -> Result<()> {
+        let score = format!(
+            "{}:{}",
+            item.state.score1.unwrap_or_default(),
+            item.state.score2.unwrap_or_default()
+        );
+        let (subject, priority) = match kind {
+            LiveKind::Live => (
+                format!("{} vs {} is live", item.team1.name, item.team2.name),
+                Priority::High,
+            ),
+            LiveKind::Score => (
+                format!("{} {} {}", item.team1.name, score, item.team2.name),
+                Priority::Normal,
+            ),
+            LiveKind::Finished => {
+                let winner = item
+                    .winner()
+                    .map(|team| format!(", {} wins", team.name))
+                    .unwrap_or_default();
+                (
+                    format!(
+                        "{} {} {} finished{}",
+                        item.team1.name, score, item.team2.name, winner
+                    ),
+                    Priority::High,
+                )
+            }
+        };
+
+        let mut context = tera::Context::new();
+        context.insert("kind", &kind);
+        context.insert("match", item);
+        context.insert("winner", &item.winner());
+        let card = item.card().with_field("Score", score);
+        let mut notice = Notice::new(subject, "csgo_alert", context)
+            .with_tag(self.game.slug())
+            .with_priority(priority)
+            .with_cards(vec![card]);
+        if let Some(url) = item.watch_url() {
+            notice = notice.with_url(url);
+        }
+
+        self.notifier
+            .notify(&notice)
+            .await
+            .context("notify live match failed")
+    }
+}
+
+#[async_trait]
+impl Task for CsgoLive {
+    async fn run(&mut self) -> Result<()> {
+        let now = Local::now().timestamp();
+        if now - self.last_refresh < self.refresh && !self.is_active(now) {
+            return Ok(());
+        }
+        debug!(
+            "run task `{}`",
+            format!("{} live", self.game).green().bold()
+        );
+
+        // yesterday for matches running past midnight, tomorrow for those starting within
+        // the lead just after it
+        let dates = Window::new(1, 1).dates();
+        let Fetched {
+            mut matches,
+            degraded,
+        } = self.provider.fetch(&dates).await?;
+        self.last_refresh = now;
+        matches.retain(|item| self.filter.matches(item));
+        if degraded {
+            warn!(
+                "some {} providers failed, keep matches missing from them",
+                self.game
+            );
+            keep_missing(&mut matches, self.matches.values().cloned());
+        }
+
+        let mut current = HashMap::new();
+        for item in matches {
+            if let Some(previous) = self.matches.get(&item.id) {
+                let kind = match (previous.state.status, item.state.status) {
+                    (Status::Finished, _) => None,
+                    (_, Status::Finished) => Some(LiveKind::Finished),
+                    (Status::Upcoming, Status::Live) => Some(LiveKind::Live),
+                    (Status::Live, Status::Live)
+                        if previous.state.score1 != item.state.score1
+                            || previous.state.score2 != item.state.score2 =>
+                    {
+                        Some(LiveKind::Score)
+                    }
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    info!(
+                        "{} match `{}` {:?}",
+                        self.game,
+                        item.id.yellow().bold(),
+                        kind
+                    );
+                    if let Err(e) = self.notify(kind, &item).await {
+                        error!("{:?}", e);
+                    }
+                }
+            }
+            current.insert(item.id.clone(), item);
+        }
+        self.matches = current;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,34 @@
 mod api;
+mod archive;
+mod calendar;
+mod changes;
+mod client;
+mod detail;
+mod error;
+mod filter;
+mod game;
+mod liquipedia;
+mod live;
+mod provider;
+mod reminder;
+mod stream;
 mod task;
+mod tournament;
+mod watchlist;
 
+pub use api::{CsgoApi, Info, Match, Player, State, Status, Team, TeamIndex, TeamInfo};
+pub use archive::{Archive, Archiver, Form, HeadToHead, Outcome, Record};
+pub use calendar::CsgoCalendar;
+pub use client::ClientOptions;
+pub use detail::{Detail, MapScore, PlayerStats, Veto, VetoKind};
+pub use error::ApiError;
+pub use filter::Filter;
+pub use game::Game;
+pub use liquipedia::Liquipedia;
+pub use live::CsgoLive;
+pub use provider::{Fetched, MatchProvider, Providers};
+pub use reminder::Reminders;
+pub use stream::{Stream, StreamUrls};
 pub use task::Csgo;
+pub use tournament::{Tournament, Tournaments, Update, UpdateKind};
+pub use watchlist::Watchlist;
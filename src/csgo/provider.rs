@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use colored::Colorize;
+use futures::future;
+
+use crate::csgo::api::{Match, Status};
+
+/// Start times of the same match reported by different sources may differ this much.
+pub(crate) const SAME_MATCH_TOLERANCE: i64 = 3 * 60 * 60;
+/// Failed fetches in a row after which a provider is reported as down.
+const FAILURE_STREAK: usize = 10;
+
+/// Matches of a fetch, `degraded` when some sources failed and matches may be missing.
+#[derive(Debug, Clone, Default)]
+pub struct Fetched {
+    pub matches: Vec<Match>,
+    pub degraded: bool,
+}
+
+/// A source of all matches of a game, tasks apply their own filters.
+#[async_trait]
+pub trait MatchProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn get_matches_by_dates(&self, dates: &[NaiveDate]) -> Result<Vec<Match>>;
+
+    /// On a degraded fetch matches of the failed sources are missing rather than gone,
+    /// a single source is never degraded.
+    async fn fetch(&self, dates: &[NaiveDate]) -> Result<Fetched> {
+        Ok(Fetched {
+            matches: self.get_matches_by_dates(dates).await?,
+            degraded: false,
+        })
+    }
+}
+
+/// Merges the matches of several providers, the first provider has the final say on
+/// matches reported by more than one of them.
+#[derive(Clone, Default)]
+pub struct Providers {
+    providers: Vec<Arc<dyn MatchProvider>>,
+    /// Failed fetches in a row, by provider name.
+    failures: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl Providers {
+    pub fn new() -> Providers {
+        Self::default()
+    }
+
+    pub fn with_provider(mut self, provider: Arc<dyn MatchProvider>) -> Self {
+        self.providers.push(provider);
+
+        self
+    }
+
+    /// A provider failing every fetch is down rather than flaky, so it is reported
+    /// once in a while instead of going unnoticed behind the healthy ones.
+    fn count_failures(&self, failed: &[&str]) {
+        let Ok(mut failures) = self.failures.lock() else {
+            return;
+        };
+        for provider in self.providers.iter() {
+            let name = provider.name();
+            if !failed.contains(&name) {
+                failures.remove(name);
+                continue;
+            }
+            let count = failures.entry(name.to_string()).or_default();
+            *count += 1;
+            if *count % FAILURE_STREAK == 0 {
+                error!(
+                    "`{}` failed {} fetches in a row, its matches are missing until it recovers",
+                    name.red().bold(),
+                    count
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl MatchProvider for Providers {
+    fn name(&self) -> &str {
+        "providers"
+    }
+
+    async fn get_matches_by_dates(&self, dates: &[NaiveDate]) -> Result<Vec<Match>> {
+        Ok(self.fetch(dates).await?.matches)
+    }
+
+    /// Tolerates failing providers as long as one succeeds, the fetch is degraded then.
+    async fn fetch(&self, dates: &[NaiveDate]) -> Result<Fetched> {
+        let results = future::join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.get_matches_by_dates(dates)),
+        )
+        .await;
+
+        let mut merged = Vec::new();
+        let mut failed = Vec::new();
+        for (provider, result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(matches) => {
+                    debug!(
+                        "get {} matches from `{}`",
+                        matches.len(),
+                        provider.name().green().bold()
+                    );
+                    merge(&mut merged, matches);
+                }
+                Err(e) => {
+                    failed.push(provider.name());
+                    error!(
+                        "get matches from `{}` failed: {:?}",
+                        provider.name().red().bold(),
+                        e
+                    );
+                }
+            }
+        }
+        self.count_failures(&failed);
+        if !failed.is_empty() && failed.len() == self.providers.len() {
+            bail!("all match providers failed");
+        }
+
+        merged.sort_by_key(|item| item.info.start_time);
+
+        Ok(Fetched {
+            matches: merged,
+            degraded: !failed.is_empty(),
+        })
+    }
+}
+
+fn merge(merged: &mut Vec<Match>, matches: Vec<Match>) {
+    for item in matches {
+        match merged.iter_mut().find(|known| is_same(known, &item)) {
+            Some(known) => reconcile(known, item),
+            None => merged.push(item),
+        }
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Teams are the same when they share a known id or their names only differ in
+/// case and punctuation.
+pub(crate) fn is_same_team(a: (i32, &str), b: (i32, &str)) -> bool {
+    (a.0 != 0 && a.0 == b.0) || normalize(a.1) == normalize(b.1)
+}
+
+pub(crate) fn is_same(a: &Match, b: &Match) -> bool {
+    if a.id == b.id {
+        return true;
+    }
+
+    let (a1, a2) = (normalize(&a.team1.name), normalize(&a.team2.name));
+    let (b1, b2) = (normalize(&b.team1.name), normalize(&b.team2.name));
+    let teams = (a1 == b1 && a2 == b2) || (a1 == b2 && a2 == b1);
+
+    teams && (a.info.start_time - b.info.start_time).abs() <= SAME_MATCH_TOLERANCE
+}
+
+/// Adds the matches of `previous` missing from `matches`, as after a degraded fetch
+/// matches of the failed providers are missing rather than gone.
+pub(crate) fn keep_missing(matches: &mut Vec<Match>, previous: impl IntoIterator<Item = Match>) {
+    for item in previous {
+        if !matches.iter().any(|known| is_same(known, &item)) {
+            matches.push(item);
+        }
+    }
+    matches.sort_by_key(|item| item.info.start_time);
+}
+
+/// Fills in what `known` lacks from `other`, `known` comes from an earlier provider.
+fn reconcile(known: &mut Match, other: Match) {
+    // team ids differ between sources, so the winner carries over by side
+    let side = other.winner_side().map(|side| side == 1);
+    let swapped = normalize(&known.team1.name) != normalize(&other.team1.name);
+    let (team1, team2, score1, score2, side) = match swapped {
+        true => (
+            other.team2,
+            other.team1,
+            other.state.score2,
+            other.state.score1,
+            side.map(|first| !first),
+        ),
+        false => (
+            other.team1,
+            other.team2,
+            other.state.score1,
+            other.state.score2,
+            side,
+        ),
+    };
+
+    for (known, other) in [(&mut known.team1, team1), (&mut known.team2, team2)] {
+        if known.logo.is_empty() {
+            known.logo = other.logo;
+        }
+        if known.rank.is_none() {
+            known.rank = other.rank;
+        }
+        if known.location.is_none() {
+            known.location = other.location;
+        }
+        if known.players.is_empty() {
+            known.players = other.players;
+        }
+    }
+    if known.info.prize.is_none() {
+        known.info.prize = other.info.prize;
+    }
+    if known.info.stage.is_none() {
+        known.info.stage = other.info.stage;
+    }
+    if known.detail.is_none() {
+        known.detail = other.detail;
+    }
+    if known.tournament.is_none() {
+        known.tournament = other.tournament;
+    }
+    // a stream without a url can not be watched, one with a url replaces it
+    if known.watch_url().is_none() && other.stream.as_ref().is_some_and(|s| s.url.is_some()) {
+        known.stream = other.stream;
+    }
+
+    // a source behind on the progress of the match should not hold it back
+    if progress(other.state.status) > progress(known.state.status) {
+        known.state.status = other.state.status;
+        known.state.score1 = score1;
+        known.state.score2 = score2;
+        known.state.winner = side.map(|first| match first {
+            true => known.team1.id,
+            false => known.team2.id,
+        });
+        known.state.winner_side = side.map(|first| match first {
+            true => 1,
+            false => 2,
+        });
+    }
+}
+
+fn progress(status: Status) -> u8 {
+    match status {
+        Status::Upcoming => 0,
+        Status::Live => 1,
+        Status::Finished => 2,
+    }
+}
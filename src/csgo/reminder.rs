@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{Local, TimeZone};
+use colored::Colorize;
+
+use crate::csgo::api::{Match, Status};
+use crate::csgo::game::Game;
+use crate::csgo::provider::keep_missing;
+use crate::notify::{Notice, Notify, Priority};
+use crate::utils::{Scheduler, Task};
+
+/// Sends a reminder for one match.
+struct Reminder {
+    notifier: Arc<dyn Notify>,
+    item: Match,
+    offset: i64,
+}
+
+#[async_trait]
+impl Task for Reminder {
+    async fn run(&mut self) -> Result<()> {
+        let subject = format!(
+            "{} vs {} starts in {} minutes",
+            self.item.team1.name, self.item.team2.name, self.offset
+        );
+        let mut context = tera::Context::new();
+        context.insert("kind", "reminder");
+        context.insert("match", &self.item);
+        let mut notice = Notice::new(subject, "csgo_alert", context)
+            .with_tag(self.item.game.slug())
+            .with_priority(Priority::High)
+            .with_cards(vec![self.item.card()]);
+        if let Some(url) = self.item.watch_url() {
+            notice = notice.with_url(url);
+        }
+
+        self.notifier
+            .notify(&notice)
+            .await
+            .context("notify reminder failed")
+    }
+}
+
+/// Keeps one job for every offset in minutes before each followed match.
+#[derive(Clone)]
+pub struct Reminders {
+    scheduler: Scheduler,
+    notifier: Arc<dyn Notify>,
+    offsets: Vec<i64>,
+    /// Matches of the last update.
+    matches: Arc<Mutex<Vec<Match>>>,
+}
+
+impl Reminders {
+    pub fn new(scheduler: Scheduler, notifier: Arc<dyn Notify>, offsets: Vec<i64>) -> Reminders {
+        Self {
+            scheduler,
+            notifier,
+            offsets,
+            matches: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Schedules reminders of `matches`, moving the ones whose start time changed and
+    /// cancelling the ones of matches no longer followed. After a `degraded` fetch the
+    /// matches of the last update missing from `matches` keep their reminders.
+    pub fn update(&self, game: Game, matches: &[Match], degraded: bool) {
+        let mut matches = matches.to_vec();
+        if let Ok(mut last) = self.matches.lock() {
+            if degraded {
+                keep_missing(
+                    &mut matches,
+                    last.drain(..)
+                        .filter(|item| item.state.status == Status::Upcoming),
+                );
+            }
+            *last = matches.clone();
+        }
+
+        let prefix = format!("{}-reminder-", game);
+        let now = Local::now();
+        let mut keys = HashSet::new();
+        for item in matches.iter() {
+            if item.state.status != Status::Upcoming {
+                continue;
+            }
+            for offset in self.offsets.iter().copied() {
+                let Some(at) = Local
+                    .timestamp_opt(item.info.start_time - offset * 60, 0)
+                    .single()
+                else {
+                    continue;
+                };
+                if at <= now {
+                    continue;
+                }
+                let key = format!("{}{}-{}", prefix, item.id, offset);
+                self.scheduler.schedule(
+                    key.clone(),
+                    at,
+                    Box::new(Reminder {
+                        notifier: self.notifier.clone(),
+                        item: item.clone(),
+                        offset,
+                    }),
+                );
+                keys.insert(key);
+            }
+        }
+
+        for key in self.scheduler.keys(&prefix) {
+            if !keys.contains(&key) {
+                self.scheduler.cancel(&key);
+            }
+        }
+        info!(
+            "schedule {} {} reminders {}",
+            keys.len(),
+            game,
+            "successfully".green().bold()
+        );
+    }
+}
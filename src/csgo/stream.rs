@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Env;
+
+const DEFAULT_PATTERNS: [(&str, &str); 5] = [
+    ("huya", "https://www.huya.com/{room}"),
+    ("douyu", "https://www.douyu.com/{room}"),
+    ("bilibili", "https://live.bilibili.com/{room}"),
+    ("twitch", "https://www.twitch.tv/{room}"),
+    ("youtube", "https://www.youtube.com/watch?v={room}"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stream {
+    pub platform: String,
+    pub room: String,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl Stream {
+    /// Streams without a platform or a room are left out.
+    pub fn new(platform: impl Into<String>, room: impl Into<String>) -> Option<Stream> {
+        let (platform, room) = (platform.into(), room.into());
+        if platform.trim().is_empty() || room.trim().is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            platform: platform.trim().to_string(),
+            room: room.trim().to_string(),
+            url: None,
+        })
+    }
+}
+
+/// Url patterns of streaming platforms, `{room}` is replaced by the room of a stream.
+#[derive(Debug, Clone)]
+pub struct StreamUrls {
+    patterns: HashMap<String, String>,
+}
+
+impl Default for StreamUrls {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATTERNS)
+    }
+}
+
+impl StreamUrls {
+    /// `patterns` are added to the built-in ones, replacing those of the same platform.
+    pub fn new(
+        patterns: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
+    ) -> StreamUrls {
+        let mut urls = Self {
+            patterns: HashMap::new(),
+        };
+        for (platform, pattern) in DEFAULT_PATTERNS.into_iter() {
+            urls.patterns
+                .insert(platform.to_string(), pattern.to_string());
+        }
+        for (platform, pattern) in patterns {
+            urls.patterns.insert(
+                platform.as_ref().trim().to_lowercase(),
+                pattern.as_ref().trim().to_string(),
+            );
+        }
+
+        urls
+    }
+
+    pub fn from_env() -> Result<StreamUrls> {
+        let patterns = Env::new("STREAM")
+            .list("URLS")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| {
+                pattern
+                    .split_once('=')
+                    .map(|(platform, pattern)| (platform.to_string(), pattern.to_string()))
+                    .ok_or(anyhow!(
+                        "stream url `{}` should be like `platform=https://host/{{room}}`",
+                        pattern
+                    ))
+            })
+            .collect::<Result<Vec<(String, String)>>>()?;
+
+        Ok(Self::new(patterns))
+    }
+
+    pub fn knows(&self, platform: &str) -> bool {
+        self.patterns.contains_key(&platform.to_lowercase())
+    }
+
+    /// Fills in the url of `stream` when its platform is known.
+    pub fn resolve(&self, mut stream: Stream) -> Stream {
+        if let Some(pattern) = self.patterns.get(&stream.platform.to_lowercase()) {
+            stream.url = Some(pattern.replace("{room}", &stream.room));
+        }
+
+        stream
+    }
+}
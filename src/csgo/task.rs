@@ -1,69 +1,220 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use chrono::{Days, Local, NaiveDate};
 use colored::Colorize;
 
-use crate::csgo::api::{CsgoApi, Match};
-use crate::utils::{Mail, Task};
-use crate::TEMPLATES;
+use crate::csgo::api::Match;
+use crate::csgo::archive::{Archive, Form};
+use crate::csgo::changes::{self, ChangeKind, Snapshot};
+use crate::csgo::filter::Filter;
+use crate::csgo::game::Game;
+use crate::csgo::provider::{keep_missing, Fetched, MatchProvider};
+use crate::csgo::reminder::Reminders;
+use crate::csgo::watchlist::Watchlist;
+use crate::notify::{Notice, Notify};
+use crate::utils::{Calendar, Event, Store, Task, Window};
+
+const FORM_SIZE: usize = 5;
 
 #[derive(Clone)]
 pub struct Csgo {
-    api: CsgoApi,
-    mail: Mail,
+    game: Game,
+    provider: Arc<dyn MatchProvider>,
+    notifier: Arc<dyn Notify>,
+    window: Window,
+    filter: Filter,
+    store: Option<Store>,
+    reminders: Option<Reminders>,
+    archive: Option<(Archive, Watchlist)>,
 }
 
 impl Csgo {
     pub fn new(
-        username: impl Into<String>,
-        password: impl Into<String>,
-        from: impl Into<String>,
-        reply_to: impl Into<String>,
-        to: impl Into<String>,
-    ) -> Result<Csgo> {
-        let api = CsgoApi::new().context("init csgo api failed")?;
-        let mail = Mail::new(username, password, from, reply_to, to).context("init mail failed")?;
-
-        Ok(Self { api, mail })
+        game: Game,
+        provider: Arc<dyn MatchProvider>,
+        notifier: Arc<dyn Notify>,
+        window: Window,
+    ) -> Csgo {
+        Self {
+            game,
+            provider,
+            notifier,
+            window,
+            filter: Filter::all(),
+            store: None,
+            reminders: None,
+            archive: None,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Csgo {
+        self.filter = filter;
+
+        self
+    }
+
+    /// Only notify when followed matches are added, removed or changed,
+    /// the last notified matches are kept in `store`.
+    pub fn with_changes(mut self, store: Store) -> Csgo {
+        self.store = Some(store);
+
+        self
+    }
+
+    /// Keeps reminders of the fetched matches up to date on every run.
+    pub fn with_reminders(mut self, reminders: Reminders) -> Csgo {
+        self.reminders = Some(reminders);
+
+        self
+    }
+
+    /// Saves fetched matches into `archive` and shows the last results of the followed
+    /// teams playing.
+    pub fn with_archive(mut self, archive: Archive, watchlist: Watchlist) -> Csgo {
+        self.archive = Some((archive, watchlist));
+
+        self
+    }
+
+    /// Forms are extras of the notice, a team whose form fails to load is left out.
+    fn forms(&self, matches: &[Match]) -> Vec<Form> {
+        let Some((archive, watchlist)) = &self.archive else {
+            return Vec::new();
+        };
+
+        matches
+            .iter()
+            .flat_map(|item| [&item.team1, &item.team2])
+            .filter(|team| watchlist.contains(team.id, &team.name))
+            .map(|team| team.name.as_str())
+            .collect::<BTreeSet<&str>>()
+            .into_iter()
+            .filter_map(|team| match archive.form(self.game, team, FORM_SIZE) {
+                Ok(form) => Some(form),
+                Err(e) => {
+                    error!("load form of `{}` failed: {:?}", team, e);
+                    None
+                }
+            })
+            .filter(|form| !form.results.is_empty())
+            .collect()
     }
 }
 
 #[async_trait]
 impl Task for Csgo {
     async fn run(&mut self) -> Result<()> {
-        info!("run task `{}`", "csgo".green().bold());
-
-        let today = Local::now().date_naive();
-        let days = vec![
-            Some(today),
-            today.checked_add_days(Days::new(1)),
-            today.checked_add_days(Days::new(2)),
-        ]
-        .into_iter()
-        .flatten()
-        .collect::<Vec<NaiveDate>>();
-
-        let mut matches = Vec::new();
-        for day in days {
-            matches.push(
-                self.api
-                    .get_matches_by_date(&day)
-                    .await
-                    .with_context(|| format!("get matches of `{}` failed", &day))?,
+        info!("run task `{}`", self.game.slug().green().bold());
+
+        let window = self.window.info();
+        let Fetched {
+            mut matches,
+            degraded,
+        } = self.provider.fetch(&self.window.dates()).await?;
+        info!("get all matches {}", "successfully".green().bold());
+
+        if degraded {
+            warn!(
+                "some {} providers failed, keep matches missing from them",
+                self.game
             );
         }
-        let matches = matches.into_iter().flatten().collect::<Vec<Match>>();
-        info!("get all matches {}", "successfully".green().bold());
+        // all matches are archived, not only the followed ones
+        if let Some((archive, _)) = &self.archive {
+            if let Err(e) = archive.save(self.game, &matches) {
+                error!("archive matches failed: {:?}", e);
+            }
+        }
+        matches.retain(|item| self.filter.matches(item));
+        if let Some(reminders) = &self.reminders {
+            reminders.update(self.game, &matches, degraded);
+        }
+
+        let snapshot_name = format!("{}.snapshot", self.game);
+        let snapshot = match &self.store {
+            Some(store) => Some(
+                store
+                    .load::<Snapshot>(&snapshot_name)
+                    .context("load snapshot failed")?
+                    .unwrap_or_default(),
+            ),
+            None => None,
+        };
+        let changes = match &snapshot {
+            Some(snapshot) => {
+                let mut changes = changes::diff(snapshot, &matches, &self.window);
+                // matches of failed providers are missing, not gone
+                if degraded {
+                    changes.retain(|change| change.kind != ChangeKind::Removed);
+                }
+                if changes.is_empty() {
+                    info!("matches not changed, {}", "skip".yellow().bold());
+                    return Ok(());
+                }
+                Some(changes)
+            }
+            None => None,
+        };
 
+        let forms = self.forms(&matches);
         let mut context = tera::Context::new();
+        context.insert("game", &self.game);
         context.insert("matches", &matches);
-        let content = TEMPLATES
-            .render("csgo.html", &context)
-            .context("render template `csgo.html` failed")?;
-        self.mail
-            .send("csgo matches near 3 days", content)
-            .context("send csgo mail failed")?;
-        info!("send mail {}", "successfully".green().bold());
+        context.insert("forms", &forms);
+        context.insert("window", &window);
+        context.insert("changes", &changes);
+        let cards = matches.iter().map(Match::card).collect();
+        let cancelled = changes
+            .iter()
+            .flatten()
+            .filter(|change| change.kind == ChangeKind::Removed)
+            .map(|change| Event {
+                cancelled: true,
+                ..change.item.event()
+            });
+        let calendar = Calendar::new(format!("{} matches", self.game))
+            .with_events(matches.iter().map(Match::event))
+            .with_events(cancelled);
+        let subject = match changes {
+            Some(_) => format!(
+                "{} matches changed from {} to {}",
+                self.game, window.start, window.end
+            ),
+            None => format!(
+                "{} matches from {} to {}",
+                self.game, window.start, window.end
+            ),
+        };
+        let notice = Notice::new(subject, "csgo", context)
+            .with_tag(self.game.slug())
+            .with_cards(cards)
+            .with_attachment(
+                format!("{}.ics", self.game),
+                "text/calendar; charset=utf-8; method=PUBLISH",
+                calendar.render(),
+            );
+        self.notifier
+            .notify(&notice)
+            .await
+            .context("notify matches failed")?;
+        info!("notify matches {}", "successfully".green().bold());
+
+        if let (Some(store), Some(snapshot)) = (&self.store, snapshot) {
+            let mut notified = matches;
+            if degraded {
+                keep_missing(
+                    &mut notified,
+                    snapshot
+                        .into_values()
+                        .filter(|item| self.window.contains(item.info.start_time)),
+                );
+            }
+            store
+                .save(&snapshot_name, &changes::snapshot(&notified))
+                .context("save snapshot failed")?;
+        }
 
         Ok(())
     }
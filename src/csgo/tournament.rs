@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{Days, Local, TimeZone};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::csgo::api::Match;
+use crate::csgo::game::Game;
+use crate::csgo::provider::MatchProvider;
+use crate::csgo::watchlist::Watchlist;
+use crate::notify::{Card, Notice, Notify};
+use crate::utils::{Store, Task, Window};
+
+const DAY: i64 = 24 * 60 * 60;
+/// Notified updates are kept a week after their event ended.
+const RETENTION: i64 = 7 * DAY;
+const PLAYOFF_STAGES: [&str; 7] = [
+    "playoff",
+    "quarter",
+    "semi",
+    "final",
+    "季后赛",
+    "淘汰赛",
+    "决赛",
+];
+
+/// An event of the event center, matches of the same event share it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    pub id: i32,
+    pub name: String,
+    #[serde(default)]
+    pub name_zh: Option<String>,
+    #[serde(default)]
+    pub logo: Option<String>,
+    pub start_time: i64,
+    pub end_time: i64,
+    #[serde(default)]
+    pub prize: Option<String>,
+    #[serde(default)]
+    pub important: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateKind {
+    /// An important event showed up for the first time.
+    New,
+    /// The event starts tomorrow.
+    Starting,
+    /// The first playoff match is within a day.
+    Playoffs,
+}
+
+impl UpdateKind {
+    fn key(self, tournament: &Tournament) -> String {
+        let kind = match self {
+            UpdateKind::New => "new",
+            UpdateKind::Starting => "starting",
+            UpdateKind::Playoffs => "playoffs",
+        };
+
+        format!("{}-{}", tournament.id, kind)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Update {
+    pub kind: UpdateKind,
+    pub tournament: Tournament,
+    /// Followed teams with matches in the event.
+    pub teams: Vec<String>,
+    /// Start of the first playoff match, only for playoffs.
+    pub playoffs: Option<i64>,
+}
+
+impl Update {
+    fn card(&self) -> Card {
+        let description = match self.kind {
+            UpdateKind::New => "New tournament",
+            UpdateKind::Starting => "Starts tomorrow",
+            UpdateKind::Playoffs => "Playoffs start",
+        };
+        let mut card = Card::new(&self.tournament.name)
+            .with_description(description)
+            .with_timestamp(self.playoffs.unwrap_or(self.tournament.start_time));
+        if let Some(prize) = &self.tournament.prize {
+            card = card.with_field("Prize", prize);
+        }
+        if !self.teams.is_empty() {
+            card = card.with_field("Teams", self.teams.join(", "));
+        }
+        if let Some(logo) = &self.tournament.logo {
+            card = card.with_icon(logo, &self.tournament.name);
+        }
+
+        card
+    }
+}
+
+/// Notified updates, keyed like `7001-starting`, with the end of their event.
+type Notified = BTreeMap<String, i64>;
+
+struct Entry {
+    tournament: Tournament,
+    teams: BTreeSet<String>,
+    playoffs: Option<i64>,
+}
+
+/// Follows the events of all matches in `window`, unfiltered, and notifies each update of
+/// an event once.
+#[derive(Clone)]
+pub struct Tournaments {
+    game: Game,
+    provider: Arc<dyn MatchProvider>,
+    watchlist: Watchlist,
+    notifier: Arc<dyn Notify>,
+    window: Window,
+    store: Store,
+}
+
+impl Tournaments {
+    pub fn new(
+        game: Game,
+        provider: Arc<dyn MatchProvider>,
+        watchlist: Watchlist,
+        notifier: Arc<dyn Notify>,
+        window: Window,
+        store: Store,
+    ) -> Tournaments {
+        Self {
+            game,
+            provider,
+            watchlist,
+            notifier,
+            window,
+            store,
+        }
+    }
+
+    fn entries(&self, matches: &[Match]) -> Vec<Entry> {
+        let mut entries = BTreeMap::<i32, Entry>::new();
+        for item in matches {
+            let Some(tournament) = &item.tournament else {
+                continue;
+            };
+            let entry = entries.entry(tournament.id).or_insert_with(|| Entry {
+                tournament: tournament.clone(),
+                teams: BTreeSet::new(),
+                playoffs: None,
+            });
+            for team in [&item.team1, &item.team2] {
+                if self.watchlist.contains(team.id, &team.name) {
+                    entry.teams.insert(team.name.clone());
+                }
+            }
+            if is_playoffs(item) {
+                let start = item.info.start_time;
+                entry.playoffs = Some(entry.playoffs.map_or(start, |first| first.min(start)));
+            }
+        }
+
+        entries.into_values().collect()
+    }
+
+    fn updates(&self, entries: Vec<Entry>, notified: &Notified, now: i64) -> Vec<Update> {
+        let tomorrow = Local::now().date_naive().checked_add_days(Days::new(1));
+        let mut updates = Vec::new();
+        for entry in entries {
+            let tournament = &entry.tournament;
+            let starts = Local
+                .timestamp_opt(tournament.start_time, 0)
+                .single()
+                .map(|time| time.date_naive());
+            let kinds = [
+                (UpdateKind::New, tournament.important),
+                (UpdateKind::Starting, starts.is_some() && starts == tomorrow),
+                (
+                    UpdateKind::Playoffs,
+                    entry
+                        .playoffs
+                        .is_some_and(|start| start >= now && start < now + DAY),
+                ),
+            ];
+            for (kind, due) in kinds {
+                if due && !notified.contains_key(&kind.key(tournament)) {
+                    updates.push(Update {
+                        kind,
+                        tournament: tournament.clone(),
+                        teams: entry.teams.iter().cloned().collect(),
+                        playoffs: entry.playoffs.filter(|_| kind == UpdateKind::Playoffs),
+                    });
+                }
+            }
+        }
+
+        updates
+    }
+}
+
+/// Stages are free text, so playoffs are told by their usual names.
+fn is_playoffs(item: &Match) -> bool {
+    let Some(stage) = &item.info.stage else {
+        return false;
+    };
+    let stage = stage.to_lowercase();
+
+    PLAYOFF_STAGES.iter().any(|name| stage.contains(name))
+}
+
+#[async_trait]
+impl Task for Tournaments {
+    async fn run(&mut self) -> Result<()> {
+        info!("run task `{} tournaments`", self.game.slug().green().bold());
+
+        let now = Local::now().timestamp();
+        let matches = self
+            .provider
+            .get_matches_by_dates(&self.window.dates())
+            .await?;
+        let name = format!("{}.tournaments", self.game);
+        let mut notified = self
+            .store
+            .load::<Notified>(&name)
+            .context("load notified tournaments failed")?
+            .unwrap_or_default();
+        notified.retain(|_, end| *end + RETENTION > now);
+
+        let updates = self.updates(self.entries(&matches), &notified, now);
+        if updates.is_empty() {
+            info!("tournaments not updated, {}", "skip".yellow().bold());
+        } else {
+            let mut context = tera::Context::new();
+            context.insert("game", &self.game);
+            context.insert("updates", &updates);
+            let cards = updates.iter().map(Update::card).collect();
+            let notice = Notice::new(
+                format!("{} tournament updates", self.game),
+                "tournament",
+                context,
+            )
+            .with_tag(self.game.slug())
+            .with_cards(cards);
+            self.notifier
+                .notify(&notice)
+                .await
+                .context("notify tournaments failed")?;
+            info!("notify tournaments {}", "successfully".green().bold());
+
+            for update in &updates {
+                notified.insert(
+                    update.kind.key(&update.tournament),
+                    update.tournament.end_time,
+                );
+            }
+        }
+
+        self.store
+            .save(&name, &notified)
+            .context("save notified tournaments failed")
+    }
+}
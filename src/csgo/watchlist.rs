@@ -0,0 +1,84 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+
+use crate::csgo::game::Game;
+
+const DEFAULT_TEAMS: [i32; 7] = [6667, 5995, 12396, 4608, 5378, 8840, 5752];
+
+/// Followed teams, given as team ids or case-insensitive team names.
+#[derive(Debug, Clone, Default)]
+pub struct Watchlist {
+    all: bool,
+    ids: HashSet<i32>,
+    names: HashSet<String>,
+    aliases: HashMap<String, String>,
+}
+
+impl Watchlist {
+    /// `teams` holds team ids, team names or aliases, `*` follows all teams.
+    pub fn new(
+        teams: impl IntoIterator<Item = impl AsRef<str>>,
+        aliases: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
+    ) -> Watchlist {
+        let aliases = aliases
+            .into_iter()
+            .map(|(alias, name)| (normalize(alias.as_ref()), normalize(name.as_ref())))
+            .collect::<HashMap<String, String>>();
+
+        let mut watchlist = Self {
+            aliases,
+            ..Default::default()
+        };
+        for team in teams {
+            let team = team.as_ref().trim();
+            if team == "*" || team.eq_ignore_ascii_case("all") {
+                watchlist.all = true;
+            } else if let Ok(id) = team.parse::<i32>() {
+                watchlist.ids.insert(id);
+            } else {
+                let name = watchlist.resolve(team);
+                watchlist.names.insert(name);
+            }
+        }
+
+        watchlist
+    }
+
+    /// Without a configured list the built-in teams are followed, for csgo only.
+    pub fn from_env(game: Game) -> Result<Watchlist> {
+        let env = game.env();
+        let aliases = env
+            .list("TEAM_ALIASES")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|alias| {
+                alias
+                    .split_once('=')
+                    .map(|(alias, name)| (alias.to_string(), name.to_string()))
+                    .ok_or(anyhow!("alias `{}` should be like `alias=name`", alias))
+            })
+            .collect::<Result<Vec<(String, String)>>>()?;
+        let teams = env.list("TEAMS").unwrap_or(match game {
+            Game::Csgo => DEFAULT_TEAMS.iter().map(|id| id.to_string()).collect(),
+            _ => Vec::new(),
+        });
+
+        Ok(Self::new(teams, aliases))
+    }
+
+    /// Lowercased team name of `name`, aliases are replaced by the name they stand for.
+    pub fn resolve(&self, name: &str) -> String {
+        let name = normalize(name);
+
+        self.aliases.get(&name).cloned().unwrap_or(name)
+    }
+
+    pub fn contains(&self, id: i32, name: &str) -> bool {
+        self.all || self.ids.contains(&id) || self.names.contains(&self.resolve(name))
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
@@ -1,4 +1,5 @@
 pub mod csgo;
+pub mod notify;
 pub mod utils;
 
 #[macro_use]
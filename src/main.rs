@@ -1,7 +1,9 @@
 use std::env;
 use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::Local;
 use colored::Colorize;
 use dotenv::dotenv;
@@ -13,12 +15,30 @@ use term_table::table_cell::{Alignment, TableCell};
 use term_table::{Table, TableStyle};
 use tokio::signal::unix::{signal, SignalKind};
 
-use iknow::csgo::Csgo;
-use iknow::utils::Manager;
+use iknow::csgo::{
+    Archive, Archiver, Csgo, CsgoApi, CsgoCalendar, CsgoLive, Filter, Game, Liquipedia,
+    MatchProvider, Providers, Record, Reminders, Tournaments, Watchlist,
+};
+use iknow::notify::{Digest, Notifier, Notify};
+use iknow::utils::{Env, Manager, Server, Store, Task, Window};
 
 #[macro_use]
 extern crate log;
 
+const DEFAULT_CALENDAR_CRON: &str = "0 0 * * * ?";
+const DEFAULT_PAST_DAYS: u64 = 0;
+const DEFAULT_FUTURE_DAYS: u64 = 2;
+const DEFAULT_LIVE_CRON: &str = "0 * * * * ?";
+const DEFAULT_LIVE_LEAD: i64 = 15;
+const DEFAULT_LIVE_REFRESH: i64 = 30;
+const DEFAULT_TOURNAMENTS_CRON: &str = "0 0 10 * * ?";
+const DEFAULT_TOURNAMENTS_FUTURE_DAYS: u64 = 7;
+const DEFAULT_ARCHIVE_CRON: &str = "0 0 6 * * ?";
+const DEFAULT_ARCHIVE_PAST_DAYS: u64 = 1;
+const TEAMS_PAST_DAYS: u64 = 7;
+const TEAMS_FUTURE_DAYS: u64 = 7;
+const HISTORY_LIMIT: usize = 20;
+
 #[tokio::main]
 async fn main() {
     dotenv().ok();
@@ -51,29 +71,136 @@ async fn main() {
 }
 
 async fn entry() -> Result<()> {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if !args.is_empty() {
+        return command(&args).await;
+    }
+
     show_banner();
 
-    let mail_username = env::var("MAIL_USERNAME").context("MAIL_USERNAME missing")?;
-    let mail_password = env::var("MAIL_PASSWORD").context("MAIL_PASSWORD missing")?;
-    let mail_from = env::var("MAIL_FROM").context("MAIL_FROM missing")?;
-    let mail_reply_to = env::var("MAIL_REPLY_TO").context("MAIL_REPLY_TO missing")?;
-    let mail_to = env::var("MAIL_TO").context("MAIL_TO missing")?;
-
-    run(
-        mail_username,
-        mail_password,
-        mail_from,
-        mail_reply_to,
-        mail_to,
-    )
-    .await
-    .context("run app failed")?;
+    let notifier = Notifier::from_env().context("init notifier failed")?;
+    if notifier.is_empty() {
+        bail!("no notify channel configured");
+    }
+
+    run(notifier).await.context("run app failed")?;
 
     listen_stop().await.context("listen stop failed")?;
 
     Ok(())
 }
 
+async fn command(args: &[String]) -> Result<()> {
+    let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+    match args.as_slice() {
+        [game, "teams", query @ ..] => teams(game.parse()?, &query.join(" ")).await,
+        [game, "history", team] => history(game.parse()?, team, None),
+        [game, "history", team1, team2] => history(game.parse()?, team1, Some(team2)),
+        _ => bail!(
+            "unknown command `{}`, usage: iknow [<game> teams <query> | <game> history <team> [<team>]]",
+            args.join(" ")
+        ),
+    }
+}
+
+async fn teams(game: Game, query: &str) -> Result<()> {
+    let api = CsgoApi::from_env(game).with_context(|| format!("init {} api failed", game))?;
+    let window = Window::new(TEAMS_PAST_DAYS, TEAMS_FUTURE_DAYS);
+    let index = api
+        .discover_teams(&window.dates())
+        .await
+        .persist(game, &Store::from_env())?;
+
+    let mut table = Table::new();
+    table.style = TableStyle::rounded();
+    let align = Alignment::Left;
+    let cell = |content: String| TableCell::builder(content).alignment(align).build();
+    table.add_row(Row::new(
+        ["id", "name", "rank", "location"].map(|title| cell(title.blue().bold().to_string())),
+    ));
+    for team in index.search(query) {
+        let rank = team.rank.map(|rank| rank.to_string()).unwrap_or_default();
+        let location = team.location.clone().unwrap_or_default();
+        table.add_row(Row::new([
+            cell(team.id.to_string().yellow().bold().to_string()),
+            cell(team.name.clone()),
+            cell(rank),
+            cell(location),
+        ]));
+    }
+
+    println!("{}", table.render());
+
+    Ok(())
+}
+
+/// Recent results of `team`, or the matches between `team` and `opponent`.
+fn history(game: Game, team: &str, opponent: Option<&str>) -> Result<()> {
+    let archive = Archive::from_env().context("open archive failed")?;
+    let watchlist = Watchlist::from_env(game).context("init watchlist failed")?;
+    let team = watchlist.resolve(team);
+    let records = match opponent {
+        Some(opponent) => {
+            let opponent = watchlist.resolve(opponent);
+            let h2h = archive.head_to_head(game, &team, &opponent, HISTORY_LIMIT)?;
+            println!(
+                "{} {} : {} {}",
+                h2h.team1.blue().bold(),
+                h2h.wins1.to_string().yellow().bold(),
+                h2h.wins2.to_string().yellow().bold(),
+                h2h.team2.red().bold()
+            );
+            h2h.records
+        }
+        None => archive.results(game, &team, HISTORY_LIMIT)?,
+    };
+    if records.is_empty() {
+        println!("no archived results of `{}`", team);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.style = TableStyle::rounded();
+    let align = Alignment::Left;
+    let cell = |content: String| TableCell::builder(content).alignment(align).build();
+    table.add_row(Row::new(
+        ["date", "event", "match", "score", "winner"]
+            .map(|title| cell(title.blue().bold().to_string())),
+    ));
+    for record in records {
+        table.add_row(Row::new(history_row(&record).map(cell)));
+    }
+
+    println!("{}", table.render());
+
+    Ok(())
+}
+
+fn history_row(record: &Record) -> [String; 5] {
+    let date = chrono::DateTime::from_timestamp(record.start_time, 0)
+        .map(|time| time.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let score = format!(
+        "{} : {}",
+        record.score1.unwrap_or_default(),
+        record.score2.unwrap_or_default()
+    );
+
+    [
+        date,
+        format!("{} {}", record.event, record.bo.to_uppercase()),
+        format!("{} vs {}", record.team1, record.team2),
+        score,
+        record
+            .winner
+            .clone()
+            .unwrap_or_default()
+            .yellow()
+            .bold()
+            .to_string(),
+    ]
+}
+
 async fn listen_stop() -> Result<()> {
     let mut sigint = signal(SignalKind::interrupt()).context("create signal interrupt failed")?;
     let mut sigterm = signal(SignalKind::terminate()).context("create signal terminate failed")?;
@@ -98,58 +225,217 @@ fn show_banner() {
     let mut table = Table::new();
     table.style = TableStyle::blank();
 
-    table.add_row(Row::new(vec![TableCell::new_with_alignment(
-        logo.yellow().bold(),
-        2,
-        Alignment::Center,
-    )]));
+    table.add_row(Row::new(vec![TableCell::builder(logo.yellow().bold())
+        .col_span(2)
+        .alignment(Alignment::Center)
+        .build()]));
     let tag_align = Alignment::Center;
     let content_align = Alignment::Center;
 
     table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("name".blue().bold(), 1, tag_align),
-        TableCell::new_with_alignment(build::PROJECT_NAME.yellow().bold(), 1, content_align),
+        TableCell::builder("name".blue().bold())
+            .alignment(tag_align)
+            .build(),
+        TableCell::builder(build::PROJECT_NAME.yellow().bold())
+            .alignment(content_align)
+            .build(),
     ]));
     table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("version".blue().bold(), 1, tag_align),
-        TableCell::new_with_alignment(build::PKG_VERSION.yellow().bold(), 1, content_align),
+        TableCell::builder("version".blue().bold())
+            .alignment(tag_align)
+            .build(),
+        TableCell::builder(build::PKG_VERSION.yellow().bold())
+            .alignment(content_align)
+            .build(),
     ]));
     table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("description".blue().bold(), 1, tag_align),
-        TableCell::new_with_alignment(build::PKG_DESCRIPTION.yellow().bold(), 1, content_align),
+        TableCell::builder("description".blue().bold())
+            .alignment(tag_align)
+            .build(),
+        TableCell::builder(build::PKG_DESCRIPTION.yellow().bold())
+            .alignment(content_align)
+            .build(),
     ]));
     table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("production".blue().bold(), 1, tag_align),
-        TableCell::new_with_alignment(build::BUILD_RUST_CHANNEL.yellow().bold(), 1, content_align),
+        TableCell::builder("production".blue().bold())
+            .alignment(tag_align)
+            .build(),
+        TableCell::builder(build::BUILD_RUST_CHANNEL.yellow().bold())
+            .alignment(content_align)
+            .build(),
     ]));
     table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("target_os".blue().bold(), 1, tag_align),
-        TableCell::new_with_alignment(build::BUILD_OS.yellow().bold(), 1, content_align),
+        TableCell::builder("target_os".blue().bold())
+            .alignment(tag_align)
+            .build(),
+        TableCell::builder(build::BUILD_OS.yellow().bold())
+            .alignment(content_align)
+            .build(),
     ]));
     table.add_row(Row::new(vec![
-        TableCell::new_with_alignment("build_env".blue().bold(), 1, tag_align),
-        TableCell::new_with_alignment(build::BUILD_TARGET.yellow().bold(), 1, content_align),
+        TableCell::builder("build_env".blue().bold())
+            .alignment(tag_align)
+            .build(),
+        TableCell::builder(build::BUILD_TARGET.yellow().bold())
+            .alignment(content_align)
+            .build(),
     ]));
 
     info!("\n\n{}", table.render());
 }
 
-async fn run(
-    username: impl Into<String>,
-    password: impl Into<String>,
-    from: impl Into<String>,
-    reply_to: impl Into<String>,
-    to: impl Into<String>,
-) -> Result<()> {
-    let csgo = Csgo::new(username, password, from, reply_to, to).context("init csgo failed")?;
-    #[cfg(debug_assertions)]
-    let manager = Manager::new()
-        .add("*/5 * * * * ?", "csgo", Box::new(csgo))
-        .context("add cron job failed")?;
-    #[cfg(not(debug_assertions))]
-    let manager = Manager::new()
-        .add("0 0 12 * * ?", "csgo", Box::new(csgo))
-        .context("add cron job failed")?;
+async fn run(notifier: Notifier) -> Result<()> {
+    let mut manager = Manager::new();
+    // alerts which can not wait for the digest
+    let alerter: Arc<dyn Notify> = Arc::new(notifier.clone());
+    let notifier: Arc<dyn Notify> = match Env::new("DIGEST").var("CRON") {
+        Some(cron) => {
+            let digest = Digest::new(notifier);
+            manager = manager
+                .add(cron, "digest", Box::new(digest.clone()))
+                .context("add cron job failed")?;
+            Arc::new(digest)
+        }
+        None => Arc::new(notifier),
+    };
+
+    let games = Env::new("IKNOW")
+        .list("GAMES")
+        .unwrap_or(vec![Game::default().to_string()])
+        .iter()
+        .map(|game| game.parse::<Game>())
+        .collect::<Result<Vec<Game>>>()?;
+    let addr = match Env::new("HTTP").var("ADDR") {
+        Some(addr) => Some(
+            addr.parse::<SocketAddr>()
+                .with_context(|| format!("parse http address `{}` failed", addr))?,
+        ),
+        None => None,
+    };
+    let mut server = Server::new();
+
+    for game in games {
+        let api = CsgoApi::from_env(game).with_context(|| format!("init {} api failed", game))?;
+        let mut providers = Providers::new().with_provider(Arc::new(api));
+        if let Some(liquipedia) = Liquipedia::from_env(game).context("init liquipedia failed")? {
+            providers = providers.with_provider(Arc::new(liquipedia));
+        }
+        let provider: Arc<dyn MatchProvider> = Arc::new(providers);
+
+        if addr.is_some() {
+            let mut calendar = CsgoCalendar::from_env(game, provider.clone())
+                .with_context(|| format!("init {} calendar failed", game))?;
+            if let Err(e) = calendar.run().await {
+                error!("{:?}", e);
+            }
+            server = server.add(
+                format!("/calendar/{}.ics", game),
+                "text/calendar; charset=utf-8",
+                calendar.content(),
+            );
+            let cron = Env::new("CALENDAR")
+                .var("CRON")
+                .unwrap_or(DEFAULT_CALENDAR_CRON.to_string());
+            manager = manager
+                .add(cron, format!("{} calendar", game), Box::new(calendar))
+                .context("add cron job failed")?;
+        }
+
+        let env = game.env();
+        if env.parse("LIVE", false)? {
+            let lead = env.parse("LIVE_LEAD", DEFAULT_LIVE_LEAD)?;
+            let refresh = env.parse("LIVE_REFRESH", DEFAULT_LIVE_REFRESH)?;
+            let live = CsgoLive::new(
+                game,
+                provider.clone(),
+                alerter.clone(),
+                lead * 60,
+                refresh * 60,
+            )
+            .with_filter(Filter::from_env_for(game, Some("LIVE")).context("init filter failed")?);
+            let cron = env
+                .var("LIVE_CRON")
+                .unwrap_or(DEFAULT_LIVE_CRON.to_string());
+            manager = manager
+                .add(cron, format!("{} live", game), Box::new(live))
+                .context("add cron job failed")?;
+        }
+
+        if env.parse("TOURNAMENTS", false)? {
+            // events are followed for all matches, the filters only apply to notified matches
+            let watchlist = Watchlist::from_env(game).context("init watchlist failed")?;
+            let env = Env::new(format!("{}_TOURNAMENTS", game));
+            let window = Window::from_env(&env, 0, DEFAULT_TOURNAMENTS_FUTURE_DAYS)?;
+            // a digest of its own, apart from the matches
+            let tournaments = Tournaments::new(
+                game,
+                provider.clone(),
+                watchlist,
+                alerter.clone(),
+                window,
+                Store::from_env(),
+            );
+            let cron = env
+                .var("CRON")
+                .unwrap_or(DEFAULT_TOURNAMENTS_CRON.to_string());
+            manager = manager
+                .add(cron, format!("{} tournaments", game), Box::new(tournaments))
+                .context("add cron job failed")?;
+        }
+
+        let archive = match env.parse("ARCHIVE", false)? {
+            true => {
+                let archive = Archive::from_env().context("open archive failed")?;
+                let env = Env::new(format!("{}_ARCHIVE", game));
+                let window = Window::from_env(&env, DEFAULT_ARCHIVE_PAST_DAYS, 0)?;
+                let archiver = Archiver::new(game, provider.clone(), archive.clone(), window);
+                let cron = env.var("CRON").unwrap_or(DEFAULT_ARCHIVE_CRON.to_string());
+                manager = manager
+                    .add(cron, format!("{} archive", game), Box::new(archiver))
+                    .context("add cron job failed")?;
+                Some(archive)
+            }
+            false => None,
+        };
+
+        let window = Window::from_env(&env, DEFAULT_PAST_DAYS, DEFAULT_FUTURE_DAYS)?;
+        let filter = Filter::from_env(game).context("init filter failed")?;
+        let mut task = Csgo::new(game, provider, notifier.clone(), window).with_filter(filter);
+        if let Some(archive) = archive {
+            let watchlist = Watchlist::from_env(game).context("init watchlist failed")?;
+            task = task.with_archive(archive, watchlist);
+        }
+        if env.parse("ONLY_CHANGES", false)? {
+            task = task.with_changes(Store::from_env());
+        }
+        if let Some(offsets) = env.list("REMINDERS") {
+            let offsets = offsets
+                .iter()
+                .map(|offset| {
+                    offset
+                        .parse::<i64>()
+                        .with_context(|| format!("parse reminder offset `{}` failed", offset))
+                })
+                .collect::<Result<Vec<i64>>>()?;
+            let reminders = Reminders::new(manager.scheduler(), alerter.clone(), offsets);
+            task = task.with_reminders(reminders);
+        }
+        #[cfg(debug_assertions)]
+        let cron = "*/5 * * * * ?";
+        #[cfg(not(debug_assertions))]
+        let cron = "0 0 12 * * ?";
+        manager = manager
+            .add(cron, game.slug(), Box::new(task))
+            .context("add cron job failed")?;
+    }
+
+    if let Some(addr) = addr {
+        tokio::spawn(async move {
+            if let Err(e) = server.start(addr).await {
+                error!("{:?}", e);
+            }
+        });
+    }
     tokio::spawn(async move {
         manager.start().await;
     });
@@ -0,0 +1,90 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::notify::{Notice, Notify, Priority};
+use crate::utils::Env;
+
+const DEFAULT_SERVER: &str = "https://api.day.app";
+
+#[derive(Clone)]
+pub struct Bark {
+    client: Client,
+    server: String,
+    device_key: String,
+}
+
+impl Bark {
+    pub fn new(server: impl Into<String>, device_key: impl Into<String>) -> Result<Bark> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            server: server.into().trim_end_matches('/').to_string(),
+            device_key: device_key.into(),
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Bark>> {
+        let env = Env::new("BARK");
+        let Some(device_key) = env.var("DEVICE_KEY") else {
+            return Ok(None);
+        };
+        let server = env.var("SERVER").unwrap_or(DEFAULT_SERVER.to_string());
+
+        Self::new(server, device_key).map(Some)
+    }
+}
+
+#[async_trait]
+impl Notify for Bark {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let level = match notice.priority() {
+            Priority::Low => "passive",
+            Priority::Normal => "active",
+            Priority::High => "timeSensitive",
+            Priority::Urgent => "critical",
+        };
+        let mut body = json!({
+            "device_key": self.device_key,
+            "title": notice.subject(),
+            "body": notice.render("md")?,
+            "level": level,
+        });
+        if let Some(group) = notice.tags().first() {
+            body["group"] = Value::from(group.as_str());
+        }
+        if let Some(url) = notice.url() {
+            body["url"] = Value::from(url);
+        }
+        if let Some(icon) = notice.icon() {
+            body["icon"] = Value::from(icon);
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/push", self.server))
+            .json(&body)
+            .send()
+            .await
+            .context("send request failed")?
+            .json::<Response>()
+            .await
+            .context("get response failed")?;
+        if response.code != 200 {
+            bail!("bark responds `{}`: {}", response.code, response.message);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    pub code: i32,
+    pub message: String,
+}
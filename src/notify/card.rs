@@ -0,0 +1,57 @@
+#[derive(Debug, Clone)]
+pub struct Icon {
+    pub url: String,
+    pub alt: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Card {
+    pub title: String,
+    pub description: Option<String>,
+    pub fields: Vec<(String, String)>,
+    pub icons: Vec<Icon>,
+    pub timestamp: Option<i64>,
+    pub url: Option<String>,
+}
+
+impl Card {
+    pub fn new(title: impl Into<String>) -> Card {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Card {
+        self.description = Some(description.into());
+
+        self
+    }
+
+    pub fn with_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Card {
+        self.fields.push((name.into(), value.into()));
+
+        self
+    }
+
+    pub fn with_icon(mut self, url: impl Into<String>, alt: impl Into<String>) -> Card {
+        self.icons.push(Icon {
+            url: url.into(),
+            alt: alt.into(),
+        });
+
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: i64) -> Card {
+        self.timestamp = Some(timestamp);
+
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Card {
+        self.url = Some(url.into());
+
+        self
+    }
+}
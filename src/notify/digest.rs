@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::notify::{Notice, Notifier, Notify, Priority};
+use crate::utils::Task;
+
+#[derive(Debug, Serialize)]
+struct Section {
+    subject: String,
+    html: String,
+    md: String,
+}
+
+/// Collects the notices of tasks and delivers them as one notice when run.
+#[derive(Clone)]
+pub struct Digest {
+    notifier: Notifier,
+    notices: Arc<Mutex<Vec<Notice>>>,
+}
+
+impl Digest {
+    pub fn new(notifier: Notifier) -> Digest {
+        Self {
+            notifier,
+            notices: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Renders `notices` as sections of one notice, a notice which fails to render is
+    /// dropped so it can not hold back the others.
+    fn digest(notices: &mut Vec<Notice>) -> Option<Notice> {
+        let mut sections = Vec::new();
+        notices.retain(|notice| {
+            let rendered = notice
+                .render("section.html")
+                .and_then(|html| Ok((html, notice.render("md")?)));
+            match rendered {
+                Ok((html, md)) => {
+                    sections.push(Section {
+                        subject: notice.subject().to_string(),
+                        html,
+                        md,
+                    });
+                    true
+                }
+                Err(e) => {
+                    error!(
+                        "drop `{}` from digest: {:?}",
+                        notice.subject().red().bold(),
+                        e
+                    );
+                    false
+                }
+            }
+        });
+        if notices.is_empty() {
+            return None;
+        }
+
+        let mut priority = Priority::Low;
+        let mut tags = Vec::new();
+        let mut cards = Vec::new();
+        let mut attachments = Vec::new();
+        for notice in notices.iter() {
+            priority = priority.max(notice.priority());
+            for tag in notice.tags() {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            cards.extend(notice.cards().iter().cloned());
+            attachments.extend(notice.attachments().iter().cloned());
+        }
+
+        let mut context = tera::Context::new();
+        context.insert("sections", &sections);
+        let mut digest = Notice::new("iknow digest", "digest", context)
+            .with_priority(priority)
+            .with_cards(cards);
+        for tag in tags {
+            digest = digest.with_tag(tag);
+        }
+        for attachment in attachments {
+            digest = digest.with_attachment(
+                attachment.filename,
+                attachment.content_type,
+                attachment.content,
+            );
+        }
+
+        Some(digest)
+    }
+}
+
+#[async_trait]
+impl Notify for Digest {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        self.notices
+            .lock()
+            .map_err(|_| anyhow!("digest lock poisoned"))?
+            .push(notice.clone());
+        info!("add `{}` to digest", notice.subject().green().bold());
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Task for Digest {
+    async fn run(&mut self) -> Result<()> {
+        info!("run task `{}`", "digest".green().bold());
+
+        let mut notices = std::mem::take(
+            &mut *self
+                .notices
+                .lock()
+                .map_err(|_| anyhow!("digest lock poisoned"))?,
+        );
+        if notices.is_empty() {
+            info!("digest is empty, {}", "skip".yellow().bold());
+            return Ok(());
+        }
+        let Some(digest) = Self::digest(&mut notices) else {
+            warn!(
+                "no notice of the digest could be rendered, {}",
+                "skip".yellow().bold()
+            );
+            return Ok(());
+        };
+
+        let result = self
+            .notifier
+            .notify(&digest)
+            .await
+            .context("notify digest failed");
+        if result.is_err() {
+            // keep the notices for the next delivery
+            if let Ok(mut pending) = self.notices.lock() {
+                notices.append(&mut pending);
+                *pending = notices;
+            }
+        }
+        result?;
+        info!("notify digest {}", "successfully".green().bold());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Local;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::notify::{sign, Notice, Notify};
+use crate::utils::Env;
+
+#[derive(Clone)]
+pub struct DingTalk {
+    client: Client,
+    webhook: Url,
+    secret: Option<String>,
+}
+
+impl DingTalk {
+    pub fn new(webhook: impl AsRef<str>, secret: Option<String>) -> Result<DingTalk> {
+        let webhook = webhook.as_ref();
+        let webhook =
+            Url::parse(webhook).with_context(|| format!("parse webhook `{}` failed", webhook))?;
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            webhook,
+            secret,
+        })
+    }
+
+    pub fn from_env() -> Result<Option<DingTalk>> {
+        let env = Env::new("DINGTALK");
+        let Some(webhook) = env.var("WEBHOOK") else {
+            return Ok(None);
+        };
+
+        Self::new(webhook, env.var("SECRET")).map(Some)
+    }
+
+    fn url(&self) -> Result<Url> {
+        let mut url = self.webhook.clone();
+        if let Some(secret) = &self.secret {
+            let timestamp = Local::now().timestamp_millis().to_string();
+            let sign = sign(
+                secret.as_bytes(),
+                format!("{}\n{}", timestamp, secret).as_bytes(),
+            )
+            .context("sign dingtalk request failed")?;
+            url.query_pairs_mut()
+                .append_pair("timestamp", &timestamp)
+                .append_pair("sign", &sign);
+        }
+
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl Notify for DingTalk {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let body = json!({
+            "msgtype": "markdown",
+            "markdown": {
+                "title": notice.subject(),
+                "text": format!("### {}\n{}", notice.subject(), notice.render("md")?),
+            },
+        });
+
+        let response = self
+            .client
+            .post(self.url()?)
+            .json(&body)
+            .send()
+            .await
+            .context("send request failed")?
+            .json::<Response>()
+            .await
+            .context("get response failed")?;
+        if response.errcode != 0 {
+            bail!(
+                "dingtalk responds `{}`: {}",
+                response.errcode,
+                response.errmsg
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    pub errcode: i32,
+    pub errmsg: String,
+}
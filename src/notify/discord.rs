@@ -0,0 +1,136 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::notify::{split, truncate, Card, Notice, Notify};
+use crate::utils::Env;
+
+/// Discord accepts at most 10 embeds and 6000 characters of embed text per message.
+const EMBEDS_MAX_COUNT: usize = 10;
+const EMBEDS_MAX_SIZE: usize = 6000;
+const CONTENT_MAX_SIZE: usize = 2000;
+
+#[derive(Clone)]
+pub struct Discord {
+    client: Client,
+    webhook: String,
+}
+
+impl Discord {
+    pub fn new(webhook: impl Into<String>) -> Result<Discord> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            webhook: webhook.into(),
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Discord>> {
+        let env = Env::new("DISCORD");
+        let Some(webhook) = env.var("WEBHOOK") else {
+            return Ok(None);
+        };
+
+        Self::new(webhook).map(Some)
+    }
+
+    fn embed(card: &Card) -> Value {
+        let mut embed = json!({
+            "title": truncate(&card.title, 256),
+            "fields": [],
+        });
+        if let Some(description) = &card.description {
+            embed["description"] = Value::from(truncate(description, 4096));
+        }
+        if let Some(url) = &card.url {
+            embed["url"] = Value::from(url.as_str());
+        }
+        let mut fields = card
+            .fields
+            .iter()
+            .map(|(name, value)| {
+                json!({
+                    "name": truncate(name, 256),
+                    "value": truncate(value, 1024),
+                    "inline": true,
+                })
+            })
+            .collect::<Vec<Value>>();
+        if let Some(timestamp) = card.timestamp {
+            fields.push(json!({
+                "name": "Start",
+                "value": format!("<t:{0}:F> (<t:{0}:R>)", timestamp),
+                "inline": false,
+            }));
+            if let Some(time) = Utc.timestamp_opt(timestamp, 0).single() {
+                embed["timestamp"] = Value::from(time.to_rfc3339());
+            }
+        }
+        fields.truncate(25);
+        embed["fields"] = Value::from(fields);
+        let mut icons = card.icons.iter();
+        if let Some(icon) = icons.next() {
+            embed["author"] = json!({
+                "name": truncate(&icon.alt, 256),
+                "icon_url": icon.url,
+            });
+        }
+        if let Some(icon) = icons.next() {
+            embed["thumbnail"] = json!({ "url": icon.url });
+        }
+
+        embed
+    }
+
+    async fn post(&self, body: &Value) -> Result<()> {
+        self.client
+            .post(&self.webhook)
+            .json(body)
+            .send()
+            .await
+            .context("send request failed")?
+            .error_for_status()
+            .context("discord responds error")?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notify for Discord {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        if notice.cards().is_empty() {
+            let content = format!("**{}**\n{}", notice.subject(), notice.render("md")?);
+            return self
+                .post(&json!({ "content": truncate(&content, CONTENT_MAX_SIZE) }))
+                .await;
+        }
+
+        let embeds = notice
+            .cards()
+            .iter()
+            .map(Self::embed)
+            .collect::<Vec<Value>>();
+        let batches = split(embeds, EMBEDS_MAX_COUNT, EMBEDS_MAX_SIZE, |embed| {
+            embed.to_string().chars().count()
+        });
+        let total = batches.len();
+        for (index, embeds) in batches.into_iter().enumerate() {
+            let content = if total > 1 {
+                format!("**{}** ({}/{})", notice.subject(), index + 1, total)
+            } else {
+                format!("**{}**", notice.subject())
+            };
+            self.post(&json!({ "content": content, "embeds": embeds }))
+                .await
+                .with_context(|| format!("send part {} of {} failed", index + 1, total))?;
+        }
+
+        Ok(())
+    }
+}
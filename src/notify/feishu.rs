@@ -0,0 +1,93 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Local;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::notify::{sign, Notice, Notify};
+use crate::utils::Env;
+
+#[derive(Clone)]
+pub struct Feishu {
+    client: Client,
+    webhook: String,
+    secret: Option<String>,
+}
+
+impl Feishu {
+    pub fn new(webhook: impl Into<String>, secret: Option<String>) -> Result<Feishu> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            webhook: webhook.into(),
+            secret,
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Feishu>> {
+        let env = Env::new("FEISHU");
+        let Some(webhook) = env.var("WEBHOOK") else {
+            return Ok(None);
+        };
+
+        Self::new(webhook, env.var("SECRET")).map(Some)
+    }
+}
+
+#[async_trait]
+impl Notify for Feishu {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let mut body = json!({
+            "msg_type": "interactive",
+            "card": {
+                "header": {
+                    "template": "blue",
+                    "title": {
+                        "tag": "plain_text",
+                        "content": notice.subject(),
+                    },
+                },
+                "elements": [{
+                    "tag": "markdown",
+                    "content": notice.render("md")?,
+                }],
+            },
+        });
+        if let Some(secret) = &self.secret {
+            // feishu signs an empty message with `timestamp\nsecret` as the key
+            let timestamp = Local::now().timestamp().to_string();
+            let sign = sign(format!("{}\n{}", timestamp, secret).as_bytes(), b"")
+                .context("sign feishu request failed")?;
+            body["timestamp"] = Value::from(timestamp);
+            body["sign"] = Value::from(sign);
+        }
+
+        let response = self
+            .client
+            .post(&self.webhook)
+            .json(&body)
+            .send()
+            .await
+            .context("send request failed")?
+            .json::<Response>()
+            .await
+            .context("get response failed")?;
+        if response.code != 0 {
+            bail!("feishu responds `{}`: {}", response.code, response.msg);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default)]
+    pub msg: String,
+}
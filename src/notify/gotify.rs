@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::notify::{Notice, Notify, Priority};
+use crate::utils::Env;
+
+#[derive(Clone)]
+pub struct Gotify {
+    client: Client,
+    server: String,
+    token: String,
+}
+
+impl Gotify {
+    pub fn new(server: impl Into<String>, token: impl Into<String>) -> Result<Gotify> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            server: server.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Gotify>> {
+        let env = Env::new("GOTIFY");
+        let Some(server) = env.var("SERVER") else {
+            return Ok(None);
+        };
+
+        Self::new(server, env.require("TOKEN")?).map(Some)
+    }
+}
+
+#[async_trait]
+impl Notify for Gotify {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let priority = match notice.priority() {
+            Priority::Low => 2,
+            Priority::Normal => 5,
+            Priority::High => 8,
+            Priority::Urgent => 10,
+        };
+        let mut body = json!({
+            "title": notice.subject(),
+            "message": notice.render("md")?,
+            "priority": priority,
+            "extras": {
+                "client::display": {
+                    "contentType": "text/markdown",
+                },
+            },
+        });
+        if let Some(url) = notice.url() {
+            body["extras"]["client::notification"] = json!({ "click": { "url": url } });
+        }
+        if let Some(icon) = notice.icon() {
+            body["extras"]["client::notification"]["bigImageUrl"] = Value::from(icon);
+        }
+
+        self.client
+            .post(format!("{}/message", self.server))
+            .header("X-Gotify-Key", &self.token)
+            .json(&body)
+            .send()
+            .await
+            .context("send request failed")?
+            .error_for_status()
+            .context("gotify responds error")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde_json::json;
+use tokio::time::{self, Duration};
+
+use crate::notify::{Notice, Notify};
+use crate::utils::Env;
+
+const RETRY_MAX_TIMES: u32 = 3;
+const RETRY_INTERVAL: u64 = 2;
+
+#[derive(Clone)]
+pub struct Matrix {
+    client: Client,
+    homeserver: Url,
+    access_token: String,
+    room_id: String,
+}
+
+impl Matrix {
+    pub fn new(
+        homeserver: impl AsRef<str>,
+        access_token: impl Into<String>,
+        room_id: impl Into<String>,
+    ) -> Result<Matrix> {
+        let homeserver = homeserver.as_ref();
+        let homeserver = Url::parse(homeserver)
+            .with_context(|| format!("parse homeserver `{}` failed", homeserver))?;
+        if homeserver.cannot_be_a_base() {
+            return Err(anyhow!("homeserver `{}` is not a base url", homeserver));
+        }
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            homeserver,
+            access_token: access_token.into(),
+            room_id: room_id.into(),
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Matrix>> {
+        let env = Env::new("MATRIX");
+        let Some(homeserver) = env.var("HOMESERVER") else {
+            return Ok(None);
+        };
+
+        Self::new(
+            homeserver,
+            env.require("ACCESS_TOKEN")?,
+            env.require("ROOM_ID")?,
+        )
+        .map(Some)
+    }
+
+    fn url(&self, txn_id: &str) -> Url {
+        let mut url = self.homeserver.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().extend([
+                "_matrix",
+                "client",
+                "v3",
+                "rooms",
+                &self.room_id,
+                "send",
+                "m.room.message",
+                txn_id,
+            ]);
+        }
+
+        url
+    }
+}
+
+#[async_trait]
+impl Notify for Matrix {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let body = json!({
+            "msgtype": "m.text",
+            "body": format!("{}\n\n{}", notice.subject(), notice.render("md")?),
+            "format": "org.matrix.custom.html",
+            "formatted_body": format!(
+                "<h3>{}</h3>\n{}",
+                tera::escape_html(notice.subject()),
+                notice.render("section.html")?
+            ),
+        });
+        // the homeserver deduplicates events sent with the same transaction id,
+        // so retrying a notice never posts it twice
+        let url = self.url(&format!("iknow.{}", notice.id()));
+
+        let mut times = 0;
+        loop {
+            times += 1;
+            let result = self
+                .client
+                .put(url.clone())
+                .bearer_auth(&self.access_token)
+                .json(&body)
+                .send()
+                .await
+                .context("send request failed")
+                .and_then(|response| response.error_for_status().context("matrix responds error"));
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if times < RETRY_MAX_TIMES => {
+                    warn!("send matrix message failed, retry {}: {:?}", times, e);
+                    time::sleep(Duration::from_secs(RETRY_INTERVAL * times as u64)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+mod bark;
+mod card;
+mod digest;
+mod dingtalk;
+mod discord;
+mod feishu;
+mod gotify;
+mod matrix;
+mod notice;
+mod ntfy;
+mod slack;
+mod wecom;
+
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use colored::Colorize;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::utils::Mail;
+
+pub use bark::Bark;
+pub use card::{Card, Icon};
+pub use digest::Digest;
+pub use dingtalk::DingTalk;
+pub use discord::Discord;
+pub use feishu::Feishu;
+pub use gotify::Gotify;
+pub use matrix::Matrix;
+pub use notice::{Attachment, Notice, Priority};
+pub use ntfy::Ntfy;
+pub use slack::Slack;
+pub use wecom::WeCom;
+
+#[async_trait]
+pub trait Notify: Send + Sync {
+    async fn notify(&self, notice: &Notice) -> Result<()>;
+}
+
+#[derive(Clone, Default)]
+pub struct Notifier {
+    channels: Vec<(String, Arc<dyn Notify>)>,
+}
+
+impl Notifier {
+    pub fn new() -> Notifier {
+        Self::default()
+    }
+
+    pub fn add(mut self, name: impl Into<String>, channel: impl Notify + 'static) -> Notifier {
+        self.channels.push((name.into(), Arc::new(channel)));
+
+        self
+    }
+
+    pub fn from_env() -> Result<Notifier> {
+        let mut notifier = Self::new();
+        if let Some(mail) = Mail::from_env().context("init mail failed")? {
+            notifier = notifier.add("mail", mail);
+        }
+        if let Some(dingtalk) = DingTalk::from_env().context("init dingtalk failed")? {
+            notifier = notifier.add("dingtalk", dingtalk);
+        }
+        if let Some(feishu) = Feishu::from_env().context("init feishu failed")? {
+            notifier = notifier.add("feishu", feishu);
+        }
+        if let Some(wecom) = WeCom::from_env().context("init wecom failed")? {
+            notifier = notifier.add("wecom", wecom);
+        }
+        if let Some(ntfy) = Ntfy::from_env().context("init ntfy failed")? {
+            notifier = notifier.add("ntfy", ntfy);
+        }
+        if let Some(gotify) = Gotify::from_env().context("init gotify failed")? {
+            notifier = notifier.add("gotify", gotify);
+        }
+        if let Some(bark) = Bark::from_env().context("init bark failed")? {
+            notifier = notifier.add("bark", bark);
+        }
+        if let Some(slack) = Slack::from_env().context("init slack failed")? {
+            notifier = notifier.add("slack", slack);
+        }
+        if let Some(discord) = Discord::from_env().context("init discord failed")? {
+            notifier = notifier.add("discord", discord);
+        }
+        if let Some(matrix) = Matrix::from_env().context("init matrix failed")? {
+            notifier = notifier.add("matrix", matrix);
+        }
+
+        Ok(notifier)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+}
+
+#[async_trait]
+impl Notify for Notifier {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let mut failed = 0;
+        for (name, channel) in self.channels.iter() {
+            match channel.notify(notice).await {
+                Ok(()) => info!(
+                    "notify `{}` {}",
+                    name.green().bold(),
+                    "successfully".green().bold()
+                ),
+                Err(e) => {
+                    failed += 1;
+                    error!("notify `{}` failed: {:?}", name.red().bold(), e);
+                }
+            }
+        }
+        if failed > 0 && failed == self.channels.len() {
+            bail!("all notify channels failed");
+        }
+
+        Ok(())
+    }
+}
+
+fn sign(key: &[u8], data: &[u8]) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).context("init hmac failed")?;
+    mac.update(data);
+
+    Ok(STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+/// Splits items into batches holding at most `max_count` items and `max_size`
+/// in total, as measured by `size`.
+fn split<T>(
+    items: Vec<T>,
+    max_count: usize,
+    max_size: usize,
+    size: impl Fn(&T) -> usize,
+) -> Vec<Vec<T>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_size = 0;
+    for item in items {
+        let item_size = size(&item);
+        if !batch.is_empty() && (batch.len() >= max_count || batch_size + item_size > max_size) {
+            batches.push(std::mem::take(&mut batch));
+            batch_size = 0;
+        }
+        batch_size += item_size;
+        batch.push(item);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let mut text = text
+        .chars()
+        .take(max_chars.saturating_sub(1))
+        .collect::<String>();
+    text.push('…');
+
+    text
+}
@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+
+use crate::notify::Card;
+use crate::TEMPLATES;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct Notice {
+    id: String,
+    subject: String,
+    template: String,
+    context: tera::Context,
+    priority: Priority,
+    tags: Vec<String>,
+    icon: Option<String>,
+    url: Option<String>,
+    cards: Vec<Card>,
+    attachments: Vec<Attachment>,
+}
+
+impl Notice {
+    pub fn new(
+        subject: impl Into<String>,
+        template: impl Into<String>,
+        context: tera::Context,
+    ) -> Notice {
+        let id = format!(
+            "{}-{}",
+            Local::now().timestamp_millis(),
+            SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        );
+
+        Self {
+            id,
+            subject: subject.into(),
+            template: template.into(),
+            context,
+            priority: Priority::default(),
+            tags: Vec::new(),
+            icon: None,
+            url: None,
+            cards: Vec::new(),
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Notice {
+        self.priority = priority;
+
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Notice {
+        self.tags.push(tag.into());
+
+        self
+    }
+
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Notice {
+        self.icon = Some(icon.into());
+
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Notice {
+        self.url = Some(url.into());
+
+        self
+    }
+
+    pub fn with_cards(mut self, cards: Vec<Card>) -> Notice {
+        self.cards = cards;
+
+        self
+    }
+
+    pub fn with_attachment(
+        mut self,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        content: impl Into<Vec<u8>>,
+    ) -> Notice {
+        self.attachments.push(Attachment {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            content: content.into(),
+        });
+
+        self
+    }
+
+    /// Unique per notice, stays the same while the notice is being retried.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    pub fn render(&self, kind: &str) -> Result<String> {
+        let name = format!("{}.{}", self.template, kind);
+
+        TEMPLATES
+            .render(&name, &self.context)
+            .with_context(|| format!("render template `{}` failed", name))
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::notify::{Notice, Notify, Priority};
+use crate::utils::Env;
+
+const DEFAULT_SERVER: &str = "https://ntfy.sh";
+
+#[derive(Clone)]
+pub struct Ntfy {
+    client: Client,
+    server: String,
+    topic: String,
+    token: Option<String>,
+}
+
+impl Ntfy {
+    pub fn new(
+        server: impl Into<String>,
+        topic: impl Into<String>,
+        token: Option<String>,
+    ) -> Result<Ntfy> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            server: server.into().trim_end_matches('/').to_string(),
+            topic: topic.into(),
+            token,
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Ntfy>> {
+        let env = Env::new("NTFY");
+        let Some(topic) = env.var("TOPIC") else {
+            return Ok(None);
+        };
+        let server = env.var("SERVER").unwrap_or(DEFAULT_SERVER.to_string());
+
+        Self::new(server, topic, env.var("TOKEN")).map(Some)
+    }
+}
+
+#[async_trait]
+impl Notify for Ntfy {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let priority = match notice.priority() {
+            Priority::Low => 2,
+            Priority::Normal => 3,
+            Priority::High => 4,
+            Priority::Urgent => 5,
+        };
+        let mut body = json!({
+            "topic": self.topic,
+            "title": notice.subject(),
+            "message": notice.render("md")?,
+            "markdown": true,
+            "priority": priority,
+            "tags": notice.tags(),
+        });
+        if let Some(url) = notice.url() {
+            body["click"] = Value::from(url);
+        }
+        if let Some(icon) = notice.icon() {
+            body["icon"] = Value::from(icon);
+        }
+
+        let mut request = self.client.post(&self.server).json(&body);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        request
+            .send()
+            .await
+            .context("send request failed")?
+            .error_for_status()
+            .context("ntfy responds error")?;
+
+        Ok(())
+    }
+}
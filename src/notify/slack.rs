@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::notify::{split, truncate, Card, Notice, Notify};
+use crate::utils::Env;
+
+/// Slack accepts at most 50 blocks per message.
+const BLOCKS_MAX_COUNT: usize = 50;
+
+#[derive(Clone)]
+pub struct Slack {
+    client: Client,
+    webhook: String,
+}
+
+impl Slack {
+    pub fn new(webhook: impl Into<String>) -> Result<Slack> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            webhook: webhook.into(),
+        })
+    }
+
+    pub fn from_env() -> Result<Option<Slack>> {
+        let env = Env::new("SLACK");
+        let Some(webhook) = env.var("WEBHOOK") else {
+            return Ok(None);
+        };
+
+        Self::new(webhook).map(Some)
+    }
+
+    fn blocks(card: &Card) -> Vec<Value> {
+        let title = match &card.url {
+            Some(url) => format!("*<{}|{}>*", url, card.title),
+            None => format!("*{}*", card.title),
+        };
+        let text = match &card.description {
+            Some(description) => format!("{}\n{}", title, description),
+            None => title,
+        };
+        let mut section = json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": truncate(&text, 3000),
+            },
+        });
+        if !card.fields.is_empty() {
+            section["fields"] = card
+                .fields
+                .iter()
+                .take(10)
+                .map(|(name, value)| {
+                    json!({
+                        "type": "mrkdwn",
+                        "text": truncate(&format!("*{}*\n{}", name, value), 2000),
+                    })
+                })
+                .collect();
+        }
+
+        let mut elements = card
+            .icons
+            .iter()
+            .map(|icon| {
+                json!({
+                    "type": "image",
+                    "image_url": icon.url,
+                    "alt_text": icon.alt,
+                })
+            })
+            .collect::<Vec<Value>>();
+        if let Some(timestamp) = card.timestamp {
+            let fallback = Utc
+                .timestamp_opt(timestamp, 0)
+                .single()
+                .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or(timestamp.to_string());
+            elements.push(json!({
+                "type": "mrkdwn",
+                "text": format!("<!date^{}^{{date_short_pretty}} {{time}}|{}>", timestamp, fallback),
+            }));
+        }
+
+        let mut blocks = vec![section];
+        if !elements.is_empty() {
+            elements.truncate(10);
+            blocks.push(json!({
+                "type": "context",
+                "elements": elements,
+            }));
+        }
+        blocks.push(json!({ "type": "divider" }));
+
+        blocks
+    }
+
+    async fn post(&self, body: &Value) -> Result<()> {
+        self.client
+            .post(&self.webhook)
+            .json(body)
+            .send()
+            .await
+            .context("send request failed")?
+            .error_for_status()
+            .context("slack responds error")?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notify for Slack {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        if notice.cards().is_empty() {
+            let text = format!("*{}*\n{}", notice.subject(), notice.render("md")?);
+            return self.post(&json!({ "text": text })).await;
+        }
+
+        // one block is reserved for the header of every part
+        let cards = notice
+            .cards()
+            .iter()
+            .map(Self::blocks)
+            .collect::<Vec<Vec<Value>>>();
+        let batches = split(cards, usize::MAX, BLOCKS_MAX_COUNT - 1, |blocks| {
+            blocks.len()
+        });
+        let total = batches.len();
+        for (index, cards) in batches.into_iter().enumerate() {
+            let header = if total > 1 {
+                format!("{} ({}/{})", notice.subject(), index + 1, total)
+            } else {
+                notice.subject().to_string()
+            };
+            let mut blocks = vec![json!({
+                "type": "header",
+                "text": {
+                    "type": "plain_text",
+                    "text": truncate(&header, 150),
+                },
+            })];
+            blocks.extend(cards.into_iter().flatten());
+            self.post(&json!({ "text": header, "blocks": blocks }))
+                .await
+                .with_context(|| format!("send part {} of {} failed", index + 1, total))?;
+        }
+
+        Ok(())
+    }
+}
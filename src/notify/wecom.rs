@@ -0,0 +1,81 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::notify::{Notice, Notify};
+use crate::utils::Env;
+
+/// Markdown messages of wecom robots are limited to 4096 bytes.
+const CONTENT_MAX_SIZE: usize = 4096;
+
+#[derive(Clone)]
+pub struct WeCom {
+    client: Client,
+    webhook: String,
+}
+
+impl WeCom {
+    pub fn new(webhook: impl Into<String>) -> Result<WeCom> {
+        let client = Client::builder()
+            .build()
+            .context("build request client failed")?;
+
+        Ok(Self {
+            client,
+            webhook: webhook.into(),
+        })
+    }
+
+    pub fn from_env() -> Result<Option<WeCom>> {
+        let env = Env::new("WECOM");
+        let Some(webhook) = env.var("WEBHOOK") else {
+            return Ok(None);
+        };
+
+        Self::new(webhook).map(Some)
+    }
+}
+
+#[async_trait]
+impl Notify for WeCom {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let mut content = format!("### {}\n{}", notice.subject(), notice.render("md")?);
+        if content.len() > CONTENT_MAX_SIZE {
+            let mut end = CONTENT_MAX_SIZE;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            content.truncate(end);
+        }
+        let body = json!({
+            "msgtype": "markdown",
+            "markdown": {
+                "content": content,
+            },
+        });
+
+        let response = self
+            .client
+            .post(&self.webhook)
+            .json(&body)
+            .send()
+            .await
+            .context("send request failed")?
+            .json::<Response>()
+            .await
+            .context("get response failed")?;
+        if response.errcode != 0 {
+            bail!("wecom responds `{}`: {}", response.errcode, response.errmsg);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    pub errcode: i32,
+    pub errmsg: String,
+}
@@ -0,0 +1,60 @@
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+
+#[derive(Debug, Clone)]
+pub struct Env {
+    prefix: String,
+}
+
+impl Env {
+    pub fn new(prefix: impl Into<String>) -> Env {
+        Self {
+            prefix: prefix.into().to_uppercase(),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}_{}", self.prefix, key)
+    }
+
+    pub fn var(&self, key: &str) -> Option<String> {
+        env::var(self.key(key))
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Comma separated values.
+    pub fn list(&self, key: &str) -> Option<Vec<String>> {
+        self.var(key).map(|value| {
+            value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+    }
+
+    pub fn require(&self, key: &str) -> Result<String> {
+        let key = self.key(key);
+        env::var(&key).with_context(|| format!("{} missing", key))
+    }
+
+    pub fn parse<T>(&self, key: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(value) = self.var(key) else {
+            return Ok(default);
+        };
+
+        value
+            .parse()
+            .map_err(|e| anyhow!("{}", e))
+            .with_context(|| format!("parse `{}` from `{}` failed", self.key(key), value))
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{TimeZone, Utc};
+
+/// Lines of iCalendar content should not be longer than 75 octets.
+const LINE_MAX_SIZE: usize = 75;
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub uid: String,
+    pub start: i64,
+    pub end: i64,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    name: String,
+    events: Vec<Event>,
+}
+
+impl Calendar {
+    pub fn new(name: impl Into<String>) -> Calendar {
+        Self {
+            name: name.into(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn with_event(mut self, event: Event) -> Calendar {
+        self.events.push(event);
+
+        self
+    }
+
+    pub fn with_events(mut self, events: impl IntoIterator<Item = Event>) -> Calendar {
+        self.events.extend(events);
+
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let now = Utc::now();
+        let stamp = now.format(TIME_FORMAT).to_string();
+        // a newer sequence makes calendar apps replace the event with the same uid
+        let sequence = now.timestamp().to_string();
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//iknow//iknow//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", escape(&self.name)),
+        ];
+        for event in self.events.iter() {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", escape(&event.uid)));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("SEQUENCE:{}", sequence));
+            lines.push(format!("DTSTART:{}", format_time(event.start)));
+            lines.push(format!("DTEND:{}", format_time(event.end)));
+            lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+            if let Some(description) = &event.description {
+                lines.push(format!("DESCRIPTION:{}", escape(description)));
+            }
+            if let Some(url) = &event.url {
+                lines.push(format!("URL:{}", url));
+            }
+            if event.cancelled {
+                lines.push("STATUS:CANCELLED".to_string());
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        lines
+            .iter()
+            .map(|line| fold(line))
+            .collect::<Vec<String>>()
+            .join("")
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_default()
+        .format(TIME_FORMAT)
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut size = 0;
+    for ch in line.chars() {
+        if size + ch.len_utf8() > LINE_MAX_SIZE {
+            folded.push_str("\r\n ");
+            size = 1;
+        }
+        size += ch.len_utf8();
+        folded.push(ch);
+    }
+    folded.push_str("\r\n");
+
+    folded
+}
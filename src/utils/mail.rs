@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use lettre::message::header::ContentType;
+use lettre::message::{Attachment as MailAttachment, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::Message;
 use lettre::{SmtpTransport, Transport};
 
+use crate::notify::{Attachment, Notice, Notify};
+use crate::utils::Env;
+
 #[derive(Clone)]
 pub struct Mail {
     client: SmtpTransport,
@@ -34,9 +39,30 @@ impl Mail {
         })
     }
 
-    pub fn send(&self, subject: impl AsRef<str>, body: impl Into<String>) -> Result<()> {
+    pub fn from_env() -> Result<Option<Mail>> {
+        let env = Env::new("MAIL");
+        if env.var("USERNAME").is_none() {
+            return Ok(None);
+        }
+
+        Self::new(
+            env.require("USERNAME")?,
+            env.require("PASSWORD")?,
+            env.require("FROM")?,
+            env.require("REPLY_TO")?,
+            env.require("TO")?,
+        )
+        .map(Some)
+    }
+
+    pub fn send(
+        &self,
+        subject: impl AsRef<str>,
+        body: impl Into<String>,
+        attachments: &[Attachment],
+    ) -> Result<()> {
         let subject = subject.as_ref();
-        let email = Message::builder()
+        let builder = Message::builder()
             .from(
                 self.from
                     .parse()
@@ -51,13 +77,36 @@ impl Mail {
                 .to
                 .parse()
                 .with_context(|| format!("parse mailbox `{}` failed", self.to))?)
-            .subject(subject)
-            .header(ContentType::TEXT_HTML)
-            .body(body.into())
-            .with_context(|| format!("init email `{}` failed", subject))?;
+            .subject(subject);
+        let email = if attachments.is_empty() {
+            builder.header(ContentType::TEXT_HTML).body(body.into())
+        } else {
+            let mut multipart = MultiPart::mixed().singlepart(SinglePart::html(body.into()));
+            for attachment in attachments {
+                let content_type =
+                    ContentType::parse(&attachment.content_type).with_context(|| {
+                        format!("parse content type `{}` failed", attachment.content_type)
+                    })?;
+                multipart = multipart.singlepart(
+                    MailAttachment::new(attachment.filename.clone())
+                        .body(attachment.content.clone(), content_type),
+                );
+            }
+            builder.multipart(multipart)
+        }
+        .with_context(|| format!("init email `{}` failed", subject))?;
 
         self.client.send(&email).context("send mail failed")?;
 
         Ok(())
     }
 }
+
+#[async_trait]
+impl Notify for Mail {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        let body = notice.render("html")?;
+
+        self.send(notice.subject(), body, notice.attachments())
+    }
+}
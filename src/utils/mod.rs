@@ -1,5 +1,15 @@
+mod env;
+mod ical;
 mod mail;
+mod server;
+mod store;
 mod task;
+mod window;
 
+pub use env::Env;
+pub use ical::{Calendar, Event};
 pub use mail::Mail;
-pub use task::{Manager, Task};
+pub use server::{Content, Server};
+pub use store::Store;
+pub use task::{Manager, Scheduler, Task};
+pub use window::{Window, WindowInfo};
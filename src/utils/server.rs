@@ -0,0 +1,62 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use colored::Colorize;
+use tokio::sync::RwLock;
+
+/// Content served by the server, `None` until it is first refreshed.
+pub type Content = Arc<RwLock<Option<String>>>;
+
+#[derive(Default)]
+pub struct Server {
+    router: Router,
+    paths: Vec<String>,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Self::default()
+    }
+
+    pub fn add(
+        mut self,
+        path: impl Into<String>,
+        content_type: &'static str,
+        content: Content,
+    ) -> Server {
+        let path = path.into();
+        self.router = self.router.route(
+            &path,
+            get(move || async move {
+                match content.read().await.as_ref() {
+                    Some(content) => {
+                        ([(header::CONTENT_TYPE, content_type)], content.clone()).into_response()
+                    }
+                    None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+                }
+            }),
+        );
+        self.paths.push(path);
+
+        self
+    }
+
+    pub async fn start(self, addr: SocketAddr) -> Result<()> {
+        for path in self.paths.iter() {
+            info!("serve `{}` on {}", path.yellow().bold(), addr);
+        }
+
+        axum::Server::try_bind(&addr)
+            .with_context(|| format!("bind address `{}` failed", addr))?
+            .serve(self.router.into_make_service())
+            .await
+            .context("serve http failed")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::utils::Env;
+
+const DEFAULT_DIR: &str = "data";
+
+/// Keeps state of tasks between runs as json files in a directory.
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub fn new(dir: impl Into<PathBuf>) -> Store {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Store {
+        let dir = Env::new("DATA")
+            .var("DIR")
+            .unwrap_or(DEFAULT_DIR.to_string());
+
+        Self::new(dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+
+    /// Returns `None` if nothing was saved under `name` yet.
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("read `{}` failed", path.display()))?;
+        let value = serde_json::from_str(&content)
+            .with_context(|| format!("parse `{}` failed", path.display()))?;
+
+        Ok(Some(value))
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("create dir `{}` failed", self.dir.display()))?;
+        let path = self.path(name);
+        let content = serde_json::to_string_pretty(value).context("serialize state failed")?;
+        fs::write(&path, content).with_context(|| format!("write `{}` failed", path.display()))?;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -52,9 +54,64 @@ impl<'a> Cron<'a> {
     }
 }
 
+struct Job {
+    at: DateTime<Local>,
+    task: Box<dyn Task>,
+}
+
+/// Handle to register one-shot jobs with a running `Manager`, jobs are keyed so
+/// they can be moved or cancelled later.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    jobs: Arc<Mutex<BTreeMap<String, Job>>>,
+}
+
+impl Scheduler {
+    /// Runs `task` once at `at`, replacing the job with the same key.
+    pub fn schedule(&self, key: impl Into<String>, at: DateTime<Local>, task: Box<dyn Task>) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.insert(key.into(), Job { at, task });
+        }
+    }
+
+    pub fn cancel(&self, key: &str) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.remove(key);
+        }
+    }
+
+    /// Keys of pending jobs starting with `prefix`.
+    pub fn keys(&self, prefix: &str) -> Vec<String> {
+        let Ok(jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+
+        jobs.keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    fn take_due(&self, now: &DateTime<Local>) -> Vec<(String, Box<dyn Task>)> {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+        let keys = jobs
+            .iter()
+            .filter(|(_, job)| &job.at < now)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<String>>();
+
+        keys.into_iter()
+            .filter_map(|key| jobs.remove(&key).map(|job| (key, job.task)))
+            .collect()
+    }
+}
+
 #[derive(Default)]
 pub struct Manager<'a> {
     crons: Vec<Cron<'a>>,
+    scheduler: Scheduler,
 }
 
 impl<'a> Manager<'a> {
@@ -76,29 +133,36 @@ impl<'a> Manager<'a> {
         Ok(self)
     }
 
+    pub fn scheduler(&self) -> Scheduler {
+        self.scheduler.clone()
+    }
+
     fn show_info(&self) {
         let mut table = Table::new();
         table.style = TableStyle::rounded();
         let tag_align = Alignment::Left;
         let content_align = Alignment::Left;
 
-        table.add_row(Row::new(vec![TableCell::new_with_alignment(
-            "manager".blue().bold(),
-            2,
-            Alignment::Center,
-        )]));
+        table.add_row(Row::new(vec![TableCell::builder("manager".blue().bold())
+            .col_span(2)
+            .alignment(Alignment::Center)
+            .build()]));
         table.add_row(Row::new(vec![
-            TableCell::new_with_alignment("task".blue().bold(), 1, tag_align),
-            TableCell::new_with_alignment("schedule".yellow().bold(), 1, content_align),
+            TableCell::builder("task".blue().bold())
+                .alignment(tag_align)
+                .build(),
+            TableCell::builder("schedule".yellow().bold())
+                .alignment(content_align)
+                .build(),
         ]));
         for cron in self.crons.iter() {
             table.add_row(Row::new(vec![
-                TableCell::new_with_alignment(cron.task_name.blue().bold(), 1, tag_align),
-                TableCell::new_with_alignment(
-                    cron.schedule_description.yellow().bold(),
-                    1,
-                    content_align,
-                ),
+                TableCell::builder(cron.task_name.blue().bold())
+                    .alignment(tag_align)
+                    .build(),
+                TableCell::builder(cron.schedule_description.yellow().bold())
+                    .alignment(content_align)
+                    .build(),
             ]));
         }
 
@@ -125,6 +189,12 @@ impl<'a> Manager<'a> {
                     }
                 }
             }
+            for (key, mut task) in self.scheduler.take_due(&now) {
+                info!("run job `{}`", key.green().bold());
+                if let Err(e) = task.run().await {
+                    error!("{:?}", e);
+                }
+            }
 
             time::sleep(Duration::from_secs(POLL_INTERVAL)).await;
         }
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+
+use crate::utils::Env;
+
+/// Days around today, both ends included.
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    pub past_days: u64,
+    pub future_days: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowInfo {
+    pub start: String,
+    pub end: String,
+    pub days: usize,
+}
+
+impl Window {
+    pub fn new(past_days: u64, future_days: u64) -> Window {
+        Self {
+            past_days,
+            future_days,
+        }
+    }
+
+    /// Reads `PAST_DAYS` and `FUTURE_DAYS` from `env`.
+    pub fn from_env(env: &Env, past_days: u64, future_days: u64) -> Result<Window> {
+        Ok(Self::new(
+            env.parse("PAST_DAYS", past_days)?,
+            env.parse("FUTURE_DAYS", future_days)?,
+        ))
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        let today = Local::now().date_naive();
+
+        today
+            .checked_sub_days(Days::new(self.past_days))
+            .unwrap_or(today)
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        let today = Local::now().date_naive();
+
+        today
+            .checked_add_days(Days::new(self.future_days))
+            .unwrap_or(today)
+    }
+
+    /// Timestamp of the first second in the window.
+    pub fn start_timestamp(&self) -> i64 {
+        timestamp(self.start())
+    }
+
+    /// Timestamp of the first second after the window.
+    pub fn end_timestamp(&self) -> i64 {
+        let end = self.end();
+
+        timestamp(end.succ_opt().unwrap_or(end))
+    }
+
+    pub fn contains(&self, timestamp: i64) -> bool {
+        timestamp >= self.start_timestamp() && timestamp < self.end_timestamp()
+    }
+
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        let end = self.end();
+
+        self.start()
+            .iter_days()
+            .take_while(|date| date <= &end)
+            .collect()
+    }
+
+    pub fn info(&self) -> WindowInfo {
+        WindowInfo {
+            start: self.start().to_string(),
+            end: self.end().to_string(),
+            days: self.dates().len(),
+        }
+    }
+}
+
+fn timestamp(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_time(Default::default()))
+        .earliest()
+        .map(|time| time.timestamp())
+        .unwrap_or_default()
+}
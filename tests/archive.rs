@@ -0,0 +1,154 @@
+use serde_json::json;
+
+use iknow::csgo::{Archive, Game, Match};
+
+const DAY: i64 = 24 * 60 * 60;
+
+fn item(id: i64, team1: &str, team2: &str, start_time: i64, score: Option<(i32, i32)>) -> Match {
+    let (index1, index2) = (id * 10, id * 10 + 1);
+    let (status, winner) = match score {
+        Some((score1, score2)) => (
+            "finished",
+            Some(if score1 > score2 { index1 } else { index2 }),
+        ),
+        None => ("upcoming", None),
+    };
+
+    serde_json::from_value(json!({
+        "id": id.to_string(),
+        "team1": {"id": index1, "name": team1, "logo": ""},
+        "team2": {"id": index2, "name": team2, "logo": ""},
+        "info": {"start_time": start_time, "bo": "bo3", "name": "IEM Katowice 2024"},
+        "state": {
+            "status": status,
+            "score1": score.map(|score| score.0),
+            "score2": score.map(|score| score.1),
+            "winner": winner,
+        },
+        "tournament": {
+            "id": 7001,
+            "name": "IEM Katowice 2024",
+            "start_time": 1707523200,
+            "end_time": 1708214400,
+            "important": true,
+        },
+    }))
+    .unwrap()
+}
+
+fn archive() -> Archive {
+    let archive = Archive::in_memory().unwrap();
+    let matches = [
+        item(1, "Natus Vincere", "FaZe", DAY, Some((2, 1))),
+        item(2, "FaZe", "Natus Vincere", 2 * DAY, Some((2, 0))),
+        item(3, "Spirit", "Natus Vincere", 3 * DAY, Some((0, 2))),
+        item(4, "Natus Vincere", "FaZe", 4 * DAY, Some((2, 1))),
+        item(5, "Natus Vincere", "Vitality", 5 * DAY, None),
+    ];
+    assert_eq!(archive.save(Game::Csgo, &matches).unwrap(), 5);
+
+    archive
+}
+
+#[test]
+fn lists_latest_results_first() {
+    let archive = archive();
+
+    let records = archive.results(Game::Csgo, "natus vincere", 3).unwrap();
+
+    let ids = records
+        .iter()
+        .map(|record| record.id.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(ids, ["4", "3", "2"]);
+    assert_eq!(records[1].winner.as_deref(), Some("Natus Vincere"));
+    assert!(archive
+        .results(Game::Dota2, "natus vincere", 3)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn shows_form_of_a_team() {
+    let archive = archive();
+
+    let form = archive.form(Game::Csgo, "NATUS VINCERE", 5).unwrap();
+
+    assert_eq!(form.team, "Natus Vincere");
+    let won = form
+        .results
+        .iter()
+        .map(|outcome| outcome.won)
+        .collect::<Vec<Option<bool>>>();
+    assert_eq!(won, [Some(true), Some(true), Some(false), Some(true)]);
+    assert_eq!(form.results[2].opponent, "FaZe");
+    assert_eq!(form.results[2].score, Some(0));
+    assert_eq!(form.results[2].opponent_score, Some(2));
+}
+
+#[test]
+fn counts_head_to_head() {
+    let archive = archive();
+
+    let h2h = archive
+        .head_to_head(Game::Csgo, "faze", "natus vincere", 10)
+        .unwrap();
+
+    assert_eq!(h2h.team1, "FaZe");
+    assert_eq!(h2h.team2, "Natus Vincere");
+    assert_eq!((h2h.wins1, h2h.wins2), (1, 2));
+    assert_eq!(h2h.records.len(), 3);
+}
+
+#[test]
+fn updates_archived_matches() {
+    let archive = archive();
+
+    archive
+        .save(
+            Game::Csgo,
+            &[item(5, "Natus Vincere", "Vitality", 5 * DAY, Some((1, 2)))],
+        )
+        .unwrap();
+
+    let records = archive.results(Game::Csgo, "vitality", 5).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].winner.as_deref(), Some("Vitality"));
+}
+
+#[test]
+fn finds_renamed_teams_by_id() {
+    let archive = archive();
+    let mut renamed = item(6, "NAVI", "Spirit", 6 * DAY, Some((2, 0)));
+    renamed.team1.id = 10;
+    renamed.state.winner = Some(10);
+    archive.save(Game::Csgo, &[renamed]).unwrap();
+
+    let form = archive.form(Game::Csgo, "natus vincere", 2).unwrap();
+    assert_eq!(form.team, "NAVI");
+    assert_eq!(form.results[0].won, Some(true));
+    assert_eq!(form.results[1].opponent, "FaZe");
+
+    let records = archive.results(Game::Csgo, "10", 5).unwrap();
+    let ids = records
+        .iter()
+        .map(|record| record.id.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(ids, ["6", "1"]);
+}
+
+#[test]
+fn replaces_matches_archived_by_another_source() {
+    let archive = archive();
+    let mut other = item(6, "natus-vincere", "FAZE", 4 * DAY + 10 * 60, Some((2, 1)));
+    other.id = "liquipedia-6".to_string();
+    (other.team1.id, other.team2.id) = (0, 0);
+    archive.save(Game::Csgo, &[other]).unwrap();
+
+    let records = archive.results(Game::Csgo, "faze", 10).unwrap();
+    let ids = records
+        .iter()
+        .map(|record| record.id.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(ids, ["liquipedia-6", "2", "1"]);
+}
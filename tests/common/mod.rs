@@ -0,0 +1,25 @@
+use std::net::TcpListener;
+
+use axum::Router;
+
+/// Serves `app` on a random local port and returns its base url.
+pub fn serve(app: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(app.into_make_service());
+    tokio::spawn(server);
+
+    format!("http://{}", addr)
+}
+
+pub fn fixture(name: &str) -> String {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+
+    std::fs::read_to_string(&path).unwrap()
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{RawQuery, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use chrono::NaiveDate;
+use common::{fixture, serve};
+
+use iknow::csgo::{ApiError, ClientOptions, CsgoApi, Game, Match, Status, StreamUrls, VetoKind};
+use iknow::utils::Store;
+
+mod common;
+
+const PATH: &str = "/eventcenter/app/csgo/event/getMatchList";
+
+#[derive(Default)]
+struct Mock {
+    responses: HashMap<String, (StatusCode, String)>,
+    queries: Mutex<Vec<String>>,
+}
+
+impl Mock {
+    fn with(mut self, date: &str, status: StatusCode, body: &str) -> Self {
+        self.responses
+            .insert(date.to_string(), (status, body.to_string()));
+
+        self
+    }
+
+    fn with_fixture(self, date: &str, name: &str) -> Self {
+        self.with(date, StatusCode::OK, &fixture(name))
+    }
+
+    fn start(self) -> (String, Arc<Mock>) {
+        let mock = Arc::new(self);
+        let app = Router::new()
+            .route(PATH, get(respond))
+            .with_state(mock.clone());
+
+        (serve(app), mock)
+    }
+
+    fn queries(&self) -> Vec<String> {
+        self.queries.lock().unwrap().clone()
+    }
+}
+
+async fn respond(State(mock): State<Arc<Mock>>, RawQuery(query): RawQuery) -> (StatusCode, String) {
+    let query = query.unwrap_or_default();
+    mock.queries.lock().unwrap().push(query.clone());
+    let param = |name: &str| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let date = param("matchTime").get(..10).unwrap_or_default().to_string();
+    let page = format!("{}/{}", date, param("pageNum"));
+
+    // a response for `date/page` wins over the one for the whole date
+    match mock.responses.get(&page).or(mock.responses.get(&date)) {
+        Some(response) => response.clone(),
+        None => (StatusCode::OK, fixture("empty")),
+    }
+}
+
+fn api(base_url: &str) -> CsgoApi {
+    let options = ClientOptions {
+        retries: 1,
+        retry_backoff: Duration::from_millis(1),
+        ..ClientOptions::default()
+    };
+
+    CsgoApi::new(2, options).unwrap().with_base_url(base_url)
+}
+
+fn ids(matches: &[Match]) -> Vec<&str> {
+    matches.iter().map(|item| item.id.as_str()).collect()
+}
+
+fn date(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+#[tokio::test]
+async fn formats_match_time() {
+    let (base_url, mock) = Mock::default().start();
+    let api = api(&base_url);
+
+    api.get_matches_by_dates(&[date("2024-02-11"), date("2024-02-12")])
+        .await
+        .unwrap();
+
+    let mut queries = mock.queries();
+    queries.sort();
+    assert_eq!(
+        queries,
+        [
+            "matchTime=2024-02-11+00:00:00&pageNum=1&pageSize=50",
+            "matchTime=2024-02-12+00:00:00&pageNum=1&pageSize=50",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn skips_malformed_matches() {
+    let (base_url, _) = Mock::default()
+        .with_fixture("2024-02-11", "matches")
+        .start();
+    let api = api(&base_url);
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    assert_eq!(ids(&matches), ["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn maps_match_fields() {
+    let (base_url, _) = Mock::default()
+        .with_fixture("2024-02-11", "matches")
+        .start();
+    let api = api(&base_url);
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    let first = &matches[0];
+    assert_eq!(first.team1.name, "Natus Vincere");
+    assert_eq!(first.team2.id, 6667);
+    assert_eq!(first.info.start_time, 1707649200);
+    assert_eq!(first.info.name, "IEM Katowice 2024");
+    assert!(first.info.important);
+    assert!(first.detail.is_none());
+    assert_eq!(first.team1.rank, Some(1));
+    let players = first
+        .team1
+        .players
+        .iter()
+        .map(|player| player.name.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(players, ["Aleksib", "b1t"]);
+    assert!(first.team2.players.is_empty());
+
+    let second = &matches[1];
+    assert_eq!(second.state.status, Status::Finished);
+    assert_eq!(
+        second.winner().map(|team| team.name.as_str()),
+        Some("Spirit")
+    );
+    let vetoes = &second.detail.as_ref().unwrap().vetoes;
+    assert_eq!(vetoes.len(), 2);
+    assert_eq!(vetoes[0].kind, VetoKind::Ban);
+    assert_eq!(vetoes[1].map, "Nuke");
+}
+
+#[tokio::test]
+async fn links_streams() {
+    let (base_url, _) = Mock::default()
+        .with_fixture("2024-02-11", "matches")
+        .start();
+    let api = api(&base_url).with_streams(StreamUrls::new([(
+        "SomePlatform",
+        "https://live.example/{room}",
+    )]));
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    let stream = matches[0].stream.as_ref().unwrap();
+    assert_eq!(stream.room, "660000");
+    assert_eq!(matches[0].watch_url(), Some("https://www.huya.com/660000"));
+    assert_eq!(
+        matches[1].watch_url(),
+        Some("https://live.example/stream-9")
+    );
+    assert!(matches[2].stream.is_none());
+}
+
+#[tokio::test]
+async fn follows_pages() {
+    let (base_url, mock) = Mock::default()
+        .with_fixture("2024-02-11/1", "page1")
+        .with_fixture("2024-02-11/2", "page2")
+        .start();
+    let api = api(&base_url);
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    assert_eq!(ids(&matches), ["1", "2", "3"]);
+    assert_eq!(mock.queries().len(), 2);
+}
+
+#[tokio::test]
+async fn stops_when_pages_repeat() {
+    let (base_url, mock) = Mock::default()
+        .with_fixture("2024-02-11", "truncated")
+        .start();
+    let api = api(&base_url);
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    assert_eq!(ids(&matches), ["1", "2", "3"]);
+    assert_eq!(mock.queries().len(), 2);
+}
+
+#[tokio::test]
+async fn stops_at_page_limit() {
+    // every page brings new matches but the day claims more than 20 pages hold
+    let mut mock = Mock::default();
+    for page in 1..=21 {
+        let mut body = serde_json::from_str::<serde_json::Value>(&fixture("page1")).unwrap();
+        let response = &mut body["result"]["matchResponse"];
+        response["itemCount"] = 1000.into();
+        for (index, item) in response["dtoList"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .enumerate()
+        {
+            item["matchId"] = (page * 10 + index).into();
+        }
+        mock = mock.with(
+            &format!("2024-02-11/{}", page),
+            StatusCode::OK,
+            &body.to_string(),
+        );
+    }
+    let (base_url, mock) = mock.start();
+    let api = api(&base_url);
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    assert_eq!(matches.len(), 40);
+    assert_eq!(ids(&matches)[39], "201");
+    assert_eq!(mock.queries().len(), 20);
+}
+
+#[tokio::test]
+async fn keeps_discovered_teams() {
+    let dir = std::env::temp_dir().join(format!("iknow-teams-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let store = Store::new(dir);
+    let (base_url, _) = Mock::default()
+        .with_fixture("2024-02-11", "matches")
+        .start();
+    let index = api(&base_url)
+        .discover_teams(&[date("2024-02-11")])
+        .await
+        .persist(Game::Csgo, &store)
+        .unwrap();
+    let known = index.search("").len();
+    assert!(known > 0);
+
+    // the day fails now, the saved teams are still found
+    let (base_url, _) = Mock::default()
+        .with("2024-02-11", StatusCode::INTERNAL_SERVER_ERROR, "")
+        .start();
+    let index = api(&base_url)
+        .discover_teams(&[date("2024-02-11")])
+        .await
+        .persist(Game::Csgo, &store)
+        .unwrap();
+
+    assert_eq!(index.search("").len(), known);
+    let navi = index.search("natus");
+    assert_eq!(navi.len(), 1);
+    assert_eq!(navi[0].id, 4608);
+}
+
+#[tokio::test]
+async fn surfaces_error_envelope() {
+    let (base_url, _) = Mock::default()
+        .with_fixture("2024-02-11", "envelope_error")
+        .start();
+    let api = api(&base_url);
+
+    let error = api
+        .get_matches_by_date(&date("2024-02-11"))
+        .await
+        .unwrap_err();
+
+    match error {
+        ApiError::Envelope { code, message } => {
+            assert_eq!(code, 500);
+            assert_eq!(message, "system busy");
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let (base_url, mock) = Mock::default()
+        .with("2024-02-11", StatusCode::SERVICE_UNAVAILABLE, "")
+        .start();
+    let api = api(&base_url);
+
+    let error = api
+        .get_matches_by_date(&date("2024-02-11"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        ApiError::Status(StatusCode::SERVICE_UNAVAILABLE)
+    ));
+    assert_eq!(mock.queries().len(), 2);
+}
+
+#[tokio::test]
+async fn reports_undecodable_body() {
+    let (base_url, mock) = Mock::default()
+        .with("2024-02-11", StatusCode::OK, "<html>maintenance</html>")
+        .start();
+    let api = api(&base_url);
+
+    let error = api
+        .get_matches_by_date(&date("2024-02-11"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, ApiError::Decode(_)));
+    assert_eq!(mock.queries().len(), 1);
+}
+
+#[tokio::test]
+async fn fails_whole_range_on_error() {
+    let (base_url, _) = Mock::default()
+        .with_fixture("2024-02-11", "matches")
+        .with_fixture("2024-02-12", "envelope_error")
+        .start();
+    let api = api(&base_url);
+
+    let error = api
+        .get_matches_by_dates(&[date("2024-02-11"), date("2024-02-12")])
+        .await
+        .unwrap_err();
+
+    assert!(format!("{:?}", error).contains("system busy"));
+}
+
+#[tokio::test]
+async fn requests_game_path() {
+    let app = Router::new().route(
+        "/eventcenter/app/dota2/event/getMatchList",
+        get(|| async { fixture("matches") }),
+    );
+    let api = api(&serve(app)).with_game(Game::Dota2);
+
+    let matches = api.get_matches_by_date(&date("2024-02-11")).await.unwrap();
+
+    assert_eq!(ids(&matches), ["1", "2", "3"]);
+    assert!(matches.iter().all(|item| item.game == Game::Dota2));
+    assert_eq!(matches[0].event().uid, "dota2-1@iknow");
+}
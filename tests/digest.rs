@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+
+use iknow::notify::{Digest, Notice, Notifier, Notify};
+use iknow::utils::Task;
+
+/// Records notices, or fails while `failing` is set.
+#[derive(Clone, Default)]
+struct Notices(Arc<Mutex<Vec<Notice>>>, Arc<AtomicBool>);
+
+#[async_trait]
+impl Notify for Notices {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        if self.1.load(Ordering::SeqCst) {
+            bail!("channel down");
+        }
+        self.0.lock().unwrap().push(notice.clone());
+
+        Ok(())
+    }
+}
+
+fn tournament(subject: &str) -> Notice {
+    let mut context = tera::Context::new();
+    context.insert("game", "csgo");
+    context.insert("updates", &Vec::<String>::new());
+
+    Notice::new(subject, "tournament", context)
+}
+
+#[tokio::test]
+async fn drops_notices_which_fail_to_render() {
+    let notices = Notices::default();
+    let mut digest = Digest::new(Notifier::new().add("recorder", notices.clone()));
+    digest.notify(&tournament("first updates")).await.unwrap();
+    digest
+        .notify(&Notice::new("broken", "missing", tera::Context::new()))
+        .await
+        .unwrap();
+    digest.notify(&tournament("second updates")).await.unwrap();
+
+    digest.run().await.unwrap();
+    // the broken notice is not kept for the next run either
+    digest.run().await.unwrap();
+
+    let notices = notices.0.lock().unwrap();
+    assert_eq!(notices.len(), 1);
+    let text = notices[0].render("md").unwrap();
+    assert!(text.contains("first updates"));
+    assert!(text.contains("second updates"));
+    assert!(!text.contains("broken"));
+}
+
+#[tokio::test]
+async fn keeps_notices_when_delivery_fails() {
+    let notices = Notices::default();
+    let mut digest = Digest::new(Notifier::new().add("recorder", notices.clone()));
+    digest.notify(&tournament("first updates")).await.unwrap();
+    notices.1.store(true, Ordering::SeqCst);
+
+    assert!(digest.run().await.is_err());
+    notices.1.store(false, Ordering::SeqCst);
+    digest.notify(&tournament("second updates")).await.unwrap();
+    digest.run().await.unwrap();
+
+    let notices = notices.0.lock().unwrap();
+    assert_eq!(notices.len(), 1);
+    let text = notices[0].render("md").unwrap();
+    assert!(text.contains("first updates"));
+    assert!(text.contains("second updates"));
+}
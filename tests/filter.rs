@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use axum::routing::get;
+use axum::Router;
+use chrono::NaiveDate;
+use common::{fixture, serve};
+use serde_json::json;
+
+use iknow::csgo::{ClientOptions, CsgoApi, Filter, Liquipedia, Match, MatchProvider, Watchlist};
+
+mod common;
+
+fn filter(text: &str) -> Filter {
+    let watchlist = Watchlist::new(["navi"], [("navi", "Natus Vincere")]);
+
+    Filter::parse(text, watchlist).unwrap()
+}
+
+fn item(team: &str, event: &str, hot: bool, important: bool) -> Match {
+    serde_json::from_value(json!({
+        "id": "1",
+        "team1": {"id": 1, "name": team, "logo": "", "rank": 3},
+        "team2": {"id": 2, "name": "FaZe", "logo": "", "rank": 25},
+        "info": {
+            "start_time": 1707652800,
+            "bo": "bo3",
+            "name": event,
+            "star": 2,
+            "hot": hot,
+            "important": important,
+        },
+    }))
+    .unwrap()
+}
+
+/// Which of `items` pass `text`.
+fn passes(text: &str, items: &[Match]) -> Vec<bool> {
+    let filter = filter(text);
+
+    items.iter().map(|item| filter.matches(item)).collect()
+}
+
+/// Ids of `items` which pass `text`.
+fn passing<'a>(text: &str, items: &'a [Match]) -> Vec<&'a str> {
+    let filter = filter(text);
+
+    items
+        .iter()
+        .filter(|item| filter.matches(item))
+        .map(|item| item.id.as_str())
+        .collect()
+}
+
+/// Matches of 2024-02-11 served from `fixture_name` at `path`.
+async fn fetched(provider: &str, path: &str, fixture_name: &'static str) -> Vec<Match> {
+    let app = Router::new().route(path, get(move || async move { fixture(fixture_name) }));
+    let base_url = serve(app);
+    let options = ClientOptions {
+        retries: 0,
+        retry_backoff: Duration::from_millis(1),
+        ..ClientOptions::default()
+    };
+    let provider: Box<dyn MatchProvider> = match provider {
+        "liquipedia" => Box::new(
+            Liquipedia::new("secret", options)
+                .unwrap()
+                .with_base_url(base_url),
+        ),
+        _ => Box::new(CsgoApi::new(1, options).unwrap().with_base_url(base_url)),
+    };
+    let dates = [NaiveDate::from_ymd_opt(2024, 2, 11).unwrap()];
+
+    provider.get_matches_by_dates(&dates).await.unwrap()
+}
+
+fn error(text: &str) -> String {
+    let watchlist = Watchlist::new(["navi"], Vec::<(String, String)>::new());
+
+    Filter::parse(text, watchlist).unwrap_err().to_string()
+}
+
+#[test]
+fn binds_not_tighter_than_and_tighter_than_or() {
+    let items = [
+        item("Spirit", "Cup", false, false),
+        item("Spirit", "Cup", false, true),
+        item("Spirit", "Cup", true, true),
+        item("Natus Vincere", "Cup", true, false),
+    ];
+
+    // ((not hot) and important) or team
+    assert_eq!(
+        passes("not hot and important or team", &items),
+        [false, true, false, true]
+    );
+    assert_eq!(
+        passes("not (hot and important) or team", &items),
+        [true, true, false, true]
+    );
+    assert_eq!(
+        passes("not hot and (important or team)", &items),
+        [false, true, false, false]
+    );
+    assert_eq!(
+        passes("NOT not hot AND important", &items),
+        [false, false, true, false]
+    );
+}
+
+#[test]
+fn compares_numbers() {
+    let items = [item("Spirit", "Cup", false, false)];
+
+    assert_eq!(passes("star >= 2 and bo = 3", &items), [true]);
+    assert_eq!(passes("star > 2 or bo != 3", &items), [false]);
+    assert_eq!(passes("rank <= 30", &items), [true]);
+    assert_eq!(passes("rank < 20", &items), [false]);
+}
+
+#[test]
+fn rejects_unbalanced_parens() {
+    assert_eq!(error("(team or hot"), "missing `)`");
+    assert_eq!(error("team or hot)"), "unexpected `Close`");
+    assert_eq!(error("()"), "unexpected `Close`");
+}
+
+#[test]
+fn rejects_missing_operands() {
+    assert_eq!(error(""), "unexpected end of filter");
+    assert_eq!(error("team and"), "unexpected end of filter");
+    assert_eq!(error("not"), "unexpected end of filter");
+    assert_eq!(error("bo 3"), "`bo` should be followed by a comparison");
+    assert_eq!(error("star >="), "`star` should be compared with a number");
+    assert_eq!(
+        error("event ~"),
+        "`event ~` should be followed by a pattern"
+    );
+}
+
+#[test]
+fn rejects_unknown_terms() {
+    assert_eq!(error("team or teams"), "unknown term `teams`");
+    assert_eq!(error("rank <= top"), "parse number `top` failed");
+    assert_eq!(error("team hot"), "unexpected `Word(\"hot\")`");
+}
+
+#[test]
+fn rejects_unterminated_strings() {
+    assert_eq!(error("event ~ \"major"), "missing closing `\"`");
+}
+
+#[test]
+fn matches_event_globs() {
+    let items = [
+        item("Spirit", "IEM Katowice Major 2024", false, false),
+        item("Spirit", "Major Qualifier", false, false),
+        item("Spirit", "ESL Pro League", false, false),
+    ];
+
+    // without `*` a pattern only has to be contained
+    assert_eq!(passes("event ~ major", &items), [true, true, false]);
+    assert_eq!(passes("event ~ \"*2024\"", &items), [true, false, false]);
+    assert_eq!(passes("event ~ \"major*\"", &items), [false, true, false]);
+    assert_eq!(
+        passes("event ~ \"iem*major*2024\"", &items),
+        [true, false, false]
+    );
+    assert_eq!(passes("event ~ \"*pro*\"", &items), [false, false, true]);
+    assert_eq!(passes("event ~ \"e*l*e\"", &items), [false, false, true]);
+    assert_eq!(passes("event ~ \"*\"", &items), [true, true, true]);
+    assert_eq!(passes("event ~ \"\"", &items), [true, true, true]);
+}
+
+#[tokio::test]
+async fn filters_fetched_matches() {
+    let items = fetched(
+        "pwesports",
+        "/eventcenter/app/csgo/event/getMatchList",
+        "matches",
+    )
+    .await;
+
+    for (text, expected) in [
+        ("all", vec!["1", "2", "3"]),
+        ("team", vec!["1"]),
+        ("important or bo = 1", vec!["1", "2"]),
+        ("event ~ \"*challenger*\"", vec!["2"]),
+        ("not team and star < 3", vec!["2", "3"]),
+        ("rank <= 20", vec!["1"]),
+        ("rank <= 30", vec!["1", "2"]),
+        ("not rank > 0", vec!["3"]),
+    ] {
+        assert_eq!(passing(text, &items), expected, "filter `{}`", text);
+    }
+}
+
+#[tokio::test]
+async fn filters_fetched_liquipedia_matches() {
+    let items = fetched("liquipedia", "/api/v3/match", "liquipedia").await;
+
+    assert_eq!(passing("team", &items), ["liquipedia-IEMK24_R01-M001"]);
+}
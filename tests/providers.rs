@@ -0,0 +1,310 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+
+use axum::extract::{RawQuery, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::get;
+use axum::Router;
+use chrono::{Local, NaiveDate};
+use common::{fixture, serve};
+use serde_json::json;
+
+use iknow::csgo::{
+    ClientOptions, Csgo, CsgoApi, Game, Liquipedia, Match, MatchProvider, Providers, Status,
+};
+use iknow::notify::{Notice, Notify};
+use iknow::utils::{Store, Task, Window};
+
+mod common;
+
+const API_KEY: &str = "secret";
+
+#[derive(Default)]
+struct Requests {
+    queries: Mutex<Vec<String>>,
+}
+
+fn options() -> ClientOptions {
+    ClientOptions {
+        retries: 0,
+        retry_backoff: Duration::from_millis(1),
+        ..ClientOptions::default()
+    }
+}
+
+fn pwesports(fixture_name: &'static str) -> CsgoApi {
+    let app = Router::new().route(
+        "/eventcenter/app/csgo/event/getMatchList",
+        get(move || async move { fixture(fixture_name) }),
+    );
+
+    CsgoApi::new(1, options())
+        .unwrap()
+        .with_base_url(serve(app))
+}
+
+fn liquipedia(fixture_name: &'static str) -> (Liquipedia, Arc<Requests>) {
+    let requests = Arc::new(Requests::default());
+    let app = Router::new()
+        .route(
+            "/api/v3/match",
+            get(
+                move |State(requests): State<Arc<Requests>>,
+                      headers: HeaderMap,
+                      RawQuery(query): RawQuery| async move {
+                    requests
+                        .queries
+                        .lock()
+                        .unwrap()
+                        .push(query.unwrap_or_default());
+                    let authorized = headers
+                        .get("authorization")
+                        .is_some_and(|value| value == format!("Apikey {}", API_KEY).as_str());
+                    match authorized {
+                        true => (StatusCode::OK, fixture(fixture_name)),
+                        false => (StatusCode::FORBIDDEN, fixture("liquipedia_error")),
+                    }
+                },
+            ),
+        )
+        .with_state(requests.clone());
+    let liquipedia = Liquipedia::new(API_KEY, options())
+        .unwrap()
+        .with_base_url(serve(app));
+
+    (liquipedia, requests)
+}
+
+fn dates() -> Vec<NaiveDate> {
+    vec![NaiveDate::from_ymd_opt(2024, 2, 11).unwrap()]
+}
+
+fn ids(matches: &[Match]) -> Vec<&str> {
+    matches.iter().map(|item| item.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn parses_liquipedia_matches() {
+    let (liquipedia, requests) = liquipedia("liquipedia");
+
+    let matches = liquipedia.get_matches_by_dates(&dates()).await.unwrap();
+
+    assert_eq!(
+        ids(&matches),
+        [
+            "liquipedia-IEMK24_R01-M001",
+            "liquipedia-ESLCL_R03-M004",
+            "liquipedia-CCT_R01-M010",
+        ]
+    );
+    let first = &matches[0];
+    assert_eq!(first.team1.name, "FaZe");
+    // liquipedia ids are not team ids
+    assert_eq!((first.team1.id, first.team2.id), (0, 0));
+    assert_eq!(first.team1.logo, "https://liquipedia.example/faze.png");
+    assert_eq!(first.info.start_time, 1707649200);
+    assert_eq!(first.info.bo, "bo3");
+    assert!(first.info.important);
+    assert_eq!(first.state.status, Status::Live);
+    assert_eq!(first.watch_url(), Some("https://www.twitch.tv/esl_csgo"));
+    let second = &matches[1];
+    assert_eq!(second.state.status, Status::Finished);
+    assert!(second.stream.is_none());
+    assert_eq!(
+        second.winner().map(|team| team.name.as_str()),
+        Some("Spirit")
+    );
+
+    let queries = requests.queries.lock().unwrap();
+    assert_eq!(queries.len(), 1);
+    assert!(queries[0].contains("wiki=counterstrike"));
+}
+
+#[tokio::test]
+async fn reports_liquipedia_errors() {
+    let (liquipedia, _) = liquipedia("liquipedia_error");
+
+    let error = liquipedia.get_matches_by_dates(&dates()).await.unwrap_err();
+
+    assert!(format!("{:?}", error).contains("Invalid API key"));
+}
+
+#[tokio::test]
+async fn merges_same_matches() {
+    let (liquipedia, _) = liquipedia("liquipedia");
+    let providers = Providers::new()
+        .with_provider(Arc::new(pwesports("matches")))
+        .with_provider(Arc::new(liquipedia));
+
+    let matches = providers.get_matches_by_dates(&dates()).await.unwrap();
+
+    assert_eq!(ids(&matches), ["1", "2", "liquipedia-CCT_R01-M010", "3"]);
+    // pwesports lists natus vincere first, liquipedia faze
+    let first = &matches[0];
+    assert_eq!(first.team1.name, "Natus Vincere");
+    assert_eq!(first.team1.logo, "https://img.example/4608.png");
+    assert_eq!(first.state.status, Status::Live);
+    assert_eq!(first.state.score1, Some(0));
+    assert_eq!(first.state.score2, Some(1));
+    let second = &matches[1];
+    assert_eq!(second.state.status, Status::Finished);
+    assert_eq!(second.state.winner, Some(7020));
+    assert!(second.detail.is_some());
+}
+
+#[tokio::test]
+async fn tolerates_failing_provider() {
+    let (liquipedia, _) = liquipedia("liquipedia_error");
+    let providers = Providers::new()
+        .with_provider(Arc::new(pwesports("matches")))
+        .with_provider(Arc::new(liquipedia));
+
+    let matches = providers.get_matches_by_dates(&dates()).await.unwrap();
+
+    assert_eq!(ids(&matches), ["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn fails_when_all_providers_fail() {
+    let (liquipedia, _) = liquipedia("liquipedia_error");
+    let providers = Providers::new()
+        .with_provider(Arc::new(pwesports("envelope_error")))
+        .with_provider(Arc::new(liquipedia));
+
+    assert!(providers.get_matches_by_dates(&dates()).await.is_err());
+}
+
+/// Serves its matches, or fails while `failing` is set.
+struct Flaky {
+    items: Mutex<Vec<Match>>,
+    failing: AtomicBool,
+}
+
+impl Flaky {
+    fn new(item: Match) -> Flaky {
+        Self {
+            items: Mutex::new(vec![item]),
+            failing: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait]
+impl MatchProvider for Flaky {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    async fn get_matches_by_dates(&self, _dates: &[NaiveDate]) -> Result<Vec<Match>> {
+        if self.failing.load(Ordering::SeqCst) {
+            bail!("source unavailable");
+        }
+
+        Ok(self.items.lock().unwrap().clone())
+    }
+}
+
+#[derive(Default)]
+struct Notices(Mutex<Vec<Notice>>);
+
+#[async_trait]
+impl Notify for Notices {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        self.0.lock().unwrap().push(notice.clone());
+
+        Ok(())
+    }
+}
+
+fn upcoming(id: &str, team1: &str, team2: &str) -> Match {
+    serde_json::from_value(json!({
+        "id": id,
+        "team1": {"id": 1, "name": team1, "logo": ""},
+        "team2": {"id": 2, "name": team2, "logo": ""},
+        "info": {"start_time": Local::now().timestamp() + 60 * 60, "bo": "bo3", "name": "Cup"},
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn keeps_snapshot_when_a_provider_fails() {
+    let dir = std::env::temp_dir().join(format!("iknow-degraded-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let flaky = Arc::new(Flaky::new(upcoming("1", "Natus Vincere", "FaZe")));
+    let steady = Arc::new(Flaky::new(upcoming("liquipedia-1", "Spirit", "Vitality")));
+    let providers = Providers::new()
+        .with_provider(flaky.clone())
+        .with_provider(steady.clone());
+    let notices = Arc::new(Notices::default());
+    let mut task = Csgo::new(
+        Game::Csgo,
+        Arc::new(providers),
+        notices.clone(),
+        Window::new(0, 1),
+    )
+    .with_changes(Store::new(&dir));
+
+    task.run().await.unwrap();
+    assert_eq!(notices.0.lock().unwrap().len(), 1);
+
+    // the match of the failing provider is not removed
+    flaky.failing.store(true, Ordering::SeqCst);
+    task.run().await.unwrap();
+    assert_eq!(notices.0.lock().unwrap().len(), 1);
+
+    // new matches of the healthy provider still go out
+    steady
+        .items
+        .lock()
+        .unwrap()
+        .push(upcoming("liquipedia-2", "G2", "MOUZ"));
+    task.run().await.unwrap();
+    {
+        let notices = notices.0.lock().unwrap();
+        assert_eq!(notices.len(), 2);
+        let text = notices[1].render("md").unwrap();
+        assert!(text.contains("G2"));
+        assert!(!text.contains("REMOVED"));
+    }
+
+    // and the snapshot knows all of them once the provider is back
+    flaky.failing.store(false, Ordering::SeqCst);
+    task.run().await.unwrap();
+    assert_eq!(notices.0.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn knows_matches_reported_by_another_source() {
+    let dir = std::env::temp_dir().join(format!("iknow-twins-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let flaky = Arc::new(Flaky::new(upcoming("1", "Natus Vincere", "FaZe")));
+    let steady = Arc::new(Flaky::new(upcoming(
+        "liquipedia-1",
+        "Natus Vincere",
+        "FaZe",
+    )));
+    let providers = Providers::new()
+        .with_provider(flaky.clone())
+        .with_provider(steady);
+    let notices = Arc::new(Notices::default());
+    let mut task = Csgo::new(
+        Game::Csgo,
+        Arc::new(providers),
+        notices.clone(),
+        Window::new(0, 1),
+    )
+    .with_changes(Store::new(&dir));
+
+    task.run().await.unwrap();
+    // the match comes under the id of the other source now, it is neither new nor gone
+    flaky.failing.store(true, Ordering::SeqCst);
+    task.run().await.unwrap();
+    flaky.failing.store(false, Ordering::SeqCst);
+    task.run().await.unwrap();
+
+    assert_eq!(notices.0.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use serde_json::json;
+
+use iknow::csgo::{Game, Match, MatchProvider, Tournaments, Watchlist};
+use iknow::notify::{Notice, Notify};
+use iknow::utils::{Store, Task, Window};
+
+const HOUR: i64 = 60 * 60;
+
+struct Matches(Vec<Match>);
+
+#[async_trait]
+impl MatchProvider for Matches {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    async fn get_matches_by_dates(&self, _dates: &[NaiveDate]) -> Result<Vec<Match>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[derive(Default)]
+struct Notices(Mutex<Vec<Notice>>);
+
+#[async_trait]
+impl Notify for Notices {
+    async fn notify(&self, notice: &Notice) -> Result<()> {
+        self.0.lock().unwrap().push(notice.clone());
+
+        Ok(())
+    }
+}
+
+fn tomorrow() -> i64 {
+    let date = Local::now()
+        .date_naive()
+        .checked_add_days(Days::new(1))
+        .unwrap();
+
+    Local
+        .from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap())
+        .earliest()
+        .unwrap()
+        .timestamp()
+}
+
+fn item(id: i64, team: &str, start_time: i64, stage: &str, tournament: serde_json::Value) -> Match {
+    serde_json::from_value(json!({
+        "id": id.to_string(),
+        "team1": {"id": id * 10, "name": team, "logo": ""},
+        "team2": {"id": id * 10 + 1, "name": "Other", "logo": ""},
+        "info": {"start_time": start_time, "bo": "bo3", "name": tournament["name"], "stage": stage},
+        "tournament": tournament,
+    }))
+    .unwrap()
+}
+
+fn matches() -> Vec<Match> {
+    let now = Local::now().timestamp();
+    let major = json!({
+        "id": 1,
+        "name": "Major",
+        "start_time": tomorrow(),
+        "end_time": tomorrow() + 10 * 24 * HOUR,
+        "prize": "$1,250,000",
+        "important": true,
+    });
+    let league = json!({
+        "id": 2,
+        "name": "League",
+        "start_time": now - 20 * 24 * HOUR,
+        "end_time": now + 24 * HOUR,
+        "important": false,
+    });
+    let cup = json!({
+        "id": 3,
+        "name": "Cup",
+        "start_time": now - 2 * 24 * HOUR,
+        "end_time": now + 4 * 24 * HOUR,
+        "important": false,
+    });
+
+    vec![
+        item(1, "Natus Vincere", tomorrow(), "Group A", major),
+        item(
+            2,
+            "FaZe",
+            now + 2 * HOUR,
+            "Playoffs - Semi-final",
+            league.clone(),
+        ),
+        item(3, "Spirit", now + 3 * HOUR, "Grand final", league),
+        item(4, "Vitality", now + 3 * HOUR, "Group B", cup),
+    ]
+}
+
+fn store(name: &str) -> Store {
+    let dir = std::env::temp_dir().join(format!("iknow-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    Store::new(dir)
+}
+
+fn tournaments(store: Store, notices: Arc<Notices>) -> Tournaments {
+    let watchlist = Watchlist::new(["natus vincere", "faze"], Vec::<(String, String)>::new());
+
+    Tournaments::new(
+        Game::Csgo,
+        Arc::new(Matches(matches())),
+        watchlist,
+        notices,
+        Window::new(0, 7),
+        store,
+    )
+}
+
+#[tokio::test]
+async fn notifies_tournament_updates() {
+    let notices = Arc::new(Notices::default());
+    let mut task = tournaments(store("updates"), notices.clone());
+
+    task.run().await.unwrap();
+
+    let notices = notices.0.lock().unwrap();
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].subject(), "csgo tournament updates");
+    assert_eq!(notices[0].cards().len(), 3);
+    let text = notices[0].render("md").unwrap();
+    assert!(text.contains("**Major** NEW TOURNAMENT"));
+    assert!(text.contains("**Major** STARTS TOMORROW"));
+    assert!(text.contains("**League** PLAYOFFS START"));
+    assert!(text.contains("$1,250,000"));
+    assert!(text.contains("Natus Vincere"));
+    assert!(text.contains("FaZe"));
+    assert!(!text.contains("Cup"));
+}
+
+#[tokio::test]
+async fn notifies_each_update_once() {
+    let store = store("once");
+    let notices = Arc::new(Notices::default());
+
+    tournaments(store.clone(), notices.clone())
+        .run()
+        .await
+        .unwrap();
+    tournaments(store, notices.clone()).run().await.unwrap();
+
+    assert_eq!(notices.0.lock().unwrap().len(), 1);
+}